@@ -1,8 +1,14 @@
 use proc_macro::TokenStream;
-use proc_macro2;
-use proc_macro2::Span;
-use quote::{format_ident, quote, quote_spanned};
-use syn::{parse_macro_input, DeriveInput, Ident, LitInt};
+use quote::{quote, quote_spanned};
+use syn::spanned::Spanned;
+use syn::{parse_macro_input, DeriveInput, Ident};
+
+mod select;
+
+use select::{
+    all_arms, arms, bind, field, find, marked_arms, may_be_empty, select, targets, Config,
+    Selection, Target,
+};
 
 /// # Example
 /// ```
@@ -38,242 +44,330 @@ use syn::{parse_macro_input, DeriveInput, Ident, LitInt};
 ///     },
 /// }
 /// ```
+///
+/// ## Merge
+/// Use `#[loc(merge)]` on several fields to get the Loc covering all of them,
+/// or `#[loc(from = a, to = b)]` on the type or variant to get the Loc from the start of `a` to the end of `b`.  
+/// Merged fields of `Option`, `Vec`, arrays or slices only need [`TryGetLoc`](trait.TryGetLoc.html) and are skipped when empty,
+/// at least one merged field must always have a Loc, otherwise use [`TryGetLoc`](derive.TryGetLoc.html)
+/// ```
+/// # use srcpos_get::*;
+/// #[derive(GetLoc)]
+/// struct Binary {
+///     #[loc(merge)]
+///     lhs: Loc,
+///     op: char,
+///     #[loc(merge)]
+///     rhs: Loc,
+/// }
+///
+/// #[derive(GetLoc)]
+/// #[loc(from = 0, to = 2)]
+/// struct Call(Loc, u8, Loc);
+///
+/// #[derive(GetLoc)]
+/// enum Expr {
+///     #[loc(from = lhs, to = rhs)]
+///     Binary { lhs: Loc, op: char, rhs: Loc },
+///     Unary(#[loc(merge)] Loc, #[loc(merge)] Loc),
+///     Call {
+///         #[loc(merge)]
+///         callee: Loc,
+///         #[loc(merge)]
+///         args: Vec<Loc>,
+///     },
+/// }
+///
+/// let a = Binary { lhs: locof!(0, 0, 0, 1), op: '+', rhs: locof!(0, 4, 0, 5) };
+/// assert_eq!(a.loc(), locof!(0, 0, 0, 5));
+/// ```
 #[proc_macro_derive(GetLoc, attributes(loc))]
 pub fn derive_get_loc(item: TokenStream) -> TokenStream {
     let input = parse_macro_input!(item as DeriveInput);
-    let name = input.ident;
-    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let cfg = Config {
+        name: "loc",
+        trait_name: "GetLoc",
+        merge: true,
+    };
+    let arms = match arms(&input, &cfg, |target, sel, ids| {
+        get_loc(&cfg, target, sel, ids)
+    }) {
+        Ok(v) => v,
+        Err(e) => return e.to_compile_error().into(),
+    };
 
-    match input.data {
-        syn::Data::Struct(v) => match v.fields {
-            syn::Fields::Named(v) => {
-                let mut has_loc = false;
-                let mut loc_id: Option<&Ident> = None;
-                for f in v.named.iter() {
-                    #[allow(unused_assignments)]
-                    if f.ident.as_ref().map(|id| id == "loc").unwrap_or(false) {
-                        has_loc = true;
-                        loc_id = f.ident.as_ref();
-                        break;
-                    }
-                    for attr in f.attrs.iter() {
-                        let path = &attr.path;
-                        if path.get_ident().map(|id| id == "loc").unwrap_or(false) {
-                            if !has_loc && loc_id.is_some() {
-                                return syn::Error::new(
-                                    Span::call_site(),
-                                    "Cannot have multiple loc",
-                                )
-                                .to_compile_error()
-                                .into();
-                            }
-                            loc_id = f.ident.as_ref();
-                            if loc_id.is_none() {
-                                return syn::Error::new(Span::call_site(), "Field has no name")
-                                    .to_compile_error()
-                                    .into();
-                            }
-                        }
-                    }
-                }
-                if loc_id.is_none() {
-                    return syn::Error::new(Span::call_site(), "Not found field loc")
-                        .to_compile_error()
-                        .into();
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let imp = quote! {
+        impl #impl_generics ::srcpos_get::GetLoc for #name #ty_generics #where_clause {
+            fn loc(&self) -> ::srcpos_get::Loc {
+                #[allow(unused_imports)]
+                use ::srcpos_get::{GetLoc, TryGetLoc};
+                match self {
+                    #(#arms),*
                 }
-                let loc_id = loc_id.unwrap();
-                let imp = quote! {
-                    impl #impl_generics ::srcpos_get::GetLoc for #name #ty_generics #where_clause {
-                        fn loc(&self) -> ::srcpos_get::Loc {
-                            use ::srcpos_get::GetLoc;
-                            self.#loc_id.loc()
-                        }
-                    }
-                };
-                return imp.into();
             }
-            syn::Fields::Unnamed(v) => {
-                let mut loc_id: Option<usize> = None;
-                for (i, f) in v.unnamed.iter().enumerate() {
-                    for attr in f.attrs.iter() {
-                        let path = &attr.path;
-                        if path.get_ident().map(|id| id == "loc").unwrap_or(false) {
-                            if loc_id.is_some() {
-                                return syn::Error::new(
-                                    Span::call_site(),
-                                    "Cannot have multiple loc",
-                                )
-                                .to_compile_error()
-                                .into();
-                            }
-                            loc_id = Some(i);
-                        }
-                    }
-                }
-                if v.unnamed.len() == 0 {
-                    return syn::Error::new(Span::call_site(), "There is nothing to get")
-                        .to_compile_error()
-                        .into();
-                }
-                if v.unnamed.len() == 1 && loc_id.is_none() {
-                    loc_id = Some(0);
-                }
-                if loc_id.is_none() {
-                    return syn::Error::new(Span::call_site(), "Not found field loc")
-                        .to_compile_error()
-                        .into();
+        }
+    };
+    imp.into()
+}
+
+/// # Example
+/// ```
+/// # use srcpos_get::*;
+/// #[derive(TryGetLoc)]
+/// struct A {
+///     #[loc]
+///     else_branch: Option<Loc>,
+/// }
+///
+/// #[derive(TryGetLoc)]
+/// struct B(Vec<Loc>);
+///
+/// #[derive(TryGetLoc)]
+/// enum C {
+///     A(Loc),
+///     B {
+///         #[loc(merge)]
+///         cond: Loc,
+///         #[loc(merge)]
+///         else_branch: Option<Loc>,
+///     },
+/// }
+///
+/// let a = A { else_branch: None };
+/// assert_eq!(a.try_loc(), None);
+/// let b = B(vec![locof!(0, 0, 0, 1), locof!(0, 4, 0, 5)]);
+/// assert_eq!(b.try_loc(), Some(locof!(0, 0, 0, 5)));
+/// ```
+#[proc_macro_derive(TryGetLoc, attributes(loc))]
+pub fn derive_try_get_loc(item: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(item as DeriveInput);
+    let cfg = Config {
+        name: "loc",
+        trait_name: "TryGetLoc",
+        merge: true,
+    };
+    let arms = match arms(&input, &cfg, |_, sel, ids| try_loc(sel, ids)) {
+        Ok(v) => v,
+        Err(e) => return e.to_compile_error().into(),
+    };
+
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let imp = quote! {
+        impl #impl_generics ::srcpos_get::TryGetLoc for #name #ty_generics #where_clause {
+            fn try_loc(&self) -> ::core::option::Option<::srcpos_get::Loc> {
+                use ::srcpos_get::TryGetLoc;
+                match self {
+                    #(#arms),*
                 }
-                let loc_id = loc_id.unwrap().to_string();
-                let loc_id = LitInt::new(loc_id.as_str(), Span::call_site());
-                let imp = quote! {
-                    impl #impl_generics ::srcpos_get::GetLoc for #name #ty_generics #where_clause {
-                        fn loc(&self) -> ::srcpos_get::Loc {
-                            use ::srcpos_get::GetLoc;
-                            self.#loc_id.loc()
-                        }
-                    }
-                };
-                return imp.into();
-            }
-            syn::Fields::Unit => {
-                return syn::Error::new(Span::call_site(), "There is nothing to get")
-                    .to_compile_error()
-                    .into();
-            }
-        },
-        syn::Data::Enum(v) => {
-            let mut vimps = vec![];
-            if v.variants.len() == 0 {
-                return syn::Error::new(Span::call_site(), "There is nothing to get")
-                    .to_compile_error()
-                    .into();
             }
-            for variant in v.variants.iter() {
-                match &variant.fields {
-                    syn::Fields::Named(v) => {
-                        let mut has_loc = false;
-                        let mut loc_id: Option<&Ident> = None;
-                        for f in v.named.iter() {
-                            #[allow(unused_assignments)]
-                            if f.ident.as_ref().map(|id| id == "loc").unwrap_or(false) {
-                                has_loc = true;
-                                loc_id = f.ident.as_ref();
-                                break;
-                            }
-                            for attr in f.attrs.iter() {
-                                let path = &attr.path;
-                                if path.get_ident().map(|id| id == "loc").unwrap_or(false) {
-                                    if !has_loc && loc_id.is_some() {
-                                        return syn::Error::new(
-                                            variant.ident.span(),
-                                            "[GetLoc] Cannot have multiple loc",
-                                        )
-                                        .to_compile_error()
-                                        .into();
-                                    }
-                                    loc_id = f.ident.as_ref();
-                                    if loc_id.is_none() {
-                                        return syn::Error::new(
-                                            variant.ident.span(),
-                                            "[GetLoc] Field has no name",
-                                        )
-                                        .to_compile_error()
-                                        .into();
-                                    }
-                                }
-                            }
-                        }
-                        if loc_id.is_none() {
-                            return syn::Error::new(
-                                variant.ident.span(),
-                                "[GetLoc] Not found field loc",
-                            )
-                            .to_compile_error()
-                            .into();
-                        }
-                        let loc_id = loc_id.unwrap();
-                        let vname = &variant.ident;
-                        let imp = quote_spanned! { variant.ident.span() => Self::#vname { #loc_id, .. } => #loc_id.loc() };
-                        vimps.push(imp);
-                    }
-                    syn::Fields::Unnamed(v) => {
-                        let mut loc_id: Option<usize> = None;
-                        for (i, f) in v.unnamed.iter().enumerate() {
-                            for attr in f.attrs.iter() {
-                                let path = &attr.path;
-                                if path.get_ident().map(|id| id == "loc").unwrap_or(false) {
-                                    if loc_id.is_some() {
-                                        return syn::Error::new(
-                                            variant.ident.span(),
-                                            "[GetLoc] Cannot have multiple loc",
-                                        )
-                                        .to_compile_error()
-                                        .into();
-                                    }
-                                    loc_id = Some(i);
-                                }
-                            }
-                        }
-                        if v.unnamed.len() == 0 {
-                            return syn::Error::new(
-                                variant.ident.span(),
-                                "[GetLoc] There is nothing to get",
-                            )
-                            .to_compile_error()
-                            .into();
-                        }
-                        if v.unnamed.len() == 1 && loc_id.is_none() {
-                            loc_id = Some(0);
-                        }
-                        if loc_id.is_none() {
-                            return syn::Error::new(
-                                variant.ident.span(),
-                                "[GetLoc] Not found field loc",
-                            )
-                            .to_compile_error()
-                            .into();
+        }
+    };
+    imp.into()
+}
+
+/// Build the `Loc` expression of the selected fields,
+/// fields of `Option`, `Vec`, arrays or slices use `try_loc` and at least one field must use `loc`
+fn get_loc(
+    cfg: &Config,
+    target: &Target,
+    sel: &Selection,
+    ids: &[Ident],
+) -> proc_macro2::TokenStream {
+    let members = sel.members();
+    let optional: Vec<bool> = members
+        .iter()
+        .map(|m| field(target.fields, m).is_some_and(|f| may_be_empty(&f.ty)))
+        .collect();
+    if optional.iter().all(|v| *v) {
+        let span = field(target.fields, members[0]).map_or(target.span, |f| f.ty.span());
+        let msg = match sel {
+            Selection::One(_) => "The field may have no Loc, use #[derive(TryGetLoc)]",
+            _ => "None of the fields always has a Loc, use #[derive(TryGetLoc)]",
+        };
+        return cfg.error(span, msg).to_compile_error();
+    }
+    match sel {
+        Selection::One(_) => {
+            let v = &ids[0];
+            quote! { #v.loc() }
+        }
+        Selection::Merge(_) => {
+            let first = optional.iter().position(|v| !v).unwrap();
+            let v = &ids[first];
+            let rest = ids
+                .iter()
+                .zip(optional.iter())
+                .enumerate()
+                .filter(|(i, _)| *i != first)
+                .map(|(_, (v, optional))| {
+                    if *optional {
+                        quote! {
+                            let loc = match #v.try_loc() {
+                                Some(b) => ::srcpos_get::merge_loc(loc, b),
+                                None => loc,
+                            };
                         }
-                        let loc_id = loc_id.unwrap();
-                        let ids = (0..(variant.fields.len())).into_iter().map(|i| {
-                            if i != loc_id {
-                                format_ident!("_")
-                            } else {
-                                format_ident!("v{}", i)
-                            }
-                        });
-                        let loc_id = format_ident!("v{}", loc_id);
-                        let vname = &variant.ident;
-                        let imp = quote_spanned! { variant.ident.span() => Self::#vname(#(#ids),*) => #loc_id.loc() };
-                        vimps.push(imp);
-                    }
-                    syn::Fields::Unit => {
-                        return syn::Error::new(
-                            variant.ident.span(),
-                            "[GetLoc] There is nothing to get",
-                        )
-                        .to_compile_error()
-                        .into();
+                    } else {
+                        quote! { let loc = ::srcpos_get::merge_loc(loc, #v.loc()); }
                     }
+                });
+            quote! {{
+                let loc = #v.loc();
+                #(#rest)*
+                loc
+            }}
+        }
+        Selection::FromTo(_, _) => {
+            let (from, to) = (&ids[0], &ids[1]);
+            match (optional[0], optional[1]) {
+                (true, _) => quote! {{
+                    let b = #to.loc();
+                    ::srcpos_get::Loc::new(#from.try_loc().map_or(b.from, |a| a.from), b.to)
+                }},
+                (_, true) => quote! {{
+                    let a = #from.loc();
+                    ::srcpos_get::Loc::new(a.from, #to.try_loc().map_or(a.to, |b| b.to))
+                }},
+                _ => quote! { ::srcpos_get::Loc::new(#from.loc().from, #to.loc().to) },
+            }
+        }
+    }
+}
+
+/// Build the `Option<Loc>` expression of the selected fields
+fn try_loc(sel: &Selection, ids: &[Ident]) -> proc_macro2::TokenStream {
+    match sel {
+        Selection::One(_) => {
+            let v = &ids[0];
+            quote! { #v.try_loc() }
+        }
+        Selection::Merge(_) => {
+            let first = &ids[0];
+            let rest = &ids[1..];
+            quote! {{
+                let loc = #first.try_loc();
+                #(let loc = match (loc, #rest.try_loc()) {
+                    (Some(a), Some(b)) => Some(::srcpos_get::merge_loc(a, b)),
+                    (a, b) => a.or(b),
+                };)*
+                loc
+            }}
+        }
+        Selection::FromTo(_, _) => {
+            let (from, to) = (&ids[0], &ids[1]);
+            quote! {
+                match (#from.try_loc(), #to.try_loc()) {
+                    (Some(a), Some(b)) => Some(::srcpos_get::Loc::new(a.from, b.to)),
+                    (a, b) => a.or(b),
                 }
             }
-            let imp = quote! {
-                impl #impl_generics ::srcpos_get::GetLoc for #name #ty_generics #where_clause {
-                    fn loc(&self) -> ::srcpos_get::Loc {
-                        use ::srcpos_get::GetLoc;
-                        match self {
-                            #(#vimps),*
-                        }
-                    }
+        }
+    }
+}
+
+/// # Example
+/// ```
+/// # use srcpos_get::*;
+/// #[derive(GetLocMut)]
+/// struct A {
+///    loc: Loc,
+/// }
+///
+/// #[derive(GetLocMut)]
+/// enum B {
+///     A(Loc),
+///     B(u8, #[loc] Box<A>),
+/// }
+///
+/// let mut a = A { loc: locof!(0, 0, 0, 0) };
+/// *a.loc_mut() = locof!(1, 2, 3, 4);
+/// assert_eq!(a.loc, locof!(1, 2, 3, 4));
+/// ```
+#[proc_macro_derive(GetLocMut, attributes(loc))]
+pub fn derive_get_loc_mut(item: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(item as DeriveInput);
+    let cfg = Config {
+        name: "loc",
+        trait_name: "GetLocMut",
+        merge: false,
+    };
+    let arms = match arms(&input, &cfg, |_, _, ids| {
+        let v = &ids[0];
+        quote! { #v.loc_mut() }
+    }) {
+        Ok(v) => v,
+        Err(e) => return e.to_compile_error().into(),
+    };
+
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let imp = quote! {
+        impl #impl_generics ::srcpos_get::GetLocMut for #name #ty_generics #where_clause {
+            fn loc_mut(&mut self) -> &mut ::srcpos_get::Loc {
+                use ::srcpos_get::GetLocMut;
+                match self {
+                    #(#arms),*
                 }
-            };
-            return imp.into();
+            }
         }
-        syn::Data::Union(_) => {
-            return syn::Error::new(Span::call_site(), "Does not support union")
-                .to_compile_error()
-                .into();
+    };
+    imp.into()
+}
+
+/// # Example
+/// ```
+/// # use srcpos_get::*;
+/// #[derive(SetLoc)]
+/// struct A {
+///    loc: Loc,
+/// }
+///
+/// #[derive(SetLoc)]
+/// enum B {
+///     A(Loc),
+///     B {
+///         #[loc]
+///         a: Option<A>,
+///         _b: u8,
+///     },
+/// }
+///
+/// let mut a = A { loc: locof!(0, 0, 0, 0) };
+/// a.set_loc(locof!(1, 2, 3, 4));
+/// assert_eq!(a.loc, locof!(1, 2, 3, 4));
+/// ```
+#[proc_macro_derive(SetLoc, attributes(loc))]
+pub fn derive_set_loc(item: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(item as DeriveInput);
+    let cfg = Config {
+        name: "loc",
+        trait_name: "SetLoc",
+        merge: false,
+    };
+    let arms = match arms(&input, &cfg, |_, _, ids| {
+        let v = &ids[0];
+        quote! { #v.set_loc(loc) }
+    }) {
+        Ok(v) => v,
+        Err(e) => return e.to_compile_error().into(),
+    };
+
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let imp = quote! {
+        impl #impl_generics ::srcpos_get::SetLoc for #name #ty_generics #where_clause {
+            fn set_loc(&mut self, loc: ::srcpos_get::Loc) {
+                use ::srcpos_get::SetLoc;
+                match self {
+                    #(#arms),*
+                }
+            }
         }
-    }
+    };
+    imp.into()
 }
 
 /// # Example
@@ -313,237 +407,425 @@ pub fn derive_get_loc(item: TokenStream) -> TokenStream {
 #[proc_macro_derive(GetPos, attributes(pos))]
 pub fn derive_get_pos(item: TokenStream) -> TokenStream {
     let input = parse_macro_input!(item as DeriveInput);
-    let name = input.ident;
+    let cfg = Config {
+        name: "pos",
+        trait_name: "GetPos",
+        merge: false,
+    };
+    let arms = match arms(&input, &cfg, |_, _, ids| {
+        let v = &ids[0];
+        quote! { #v.pos() }
+    }) {
+        Ok(v) => v,
+        Err(e) => return e.to_compile_error().into(),
+    };
+
+    let name = &input.ident;
     let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let imp = quote! {
+        impl #impl_generics ::srcpos_get::GetPos for #name #ty_generics #where_clause {
+            fn pos(&self) -> ::srcpos_get::Pos {
+                use ::srcpos_get::GetPos;
+                match self {
+                    #(#arms),*
+                }
+            }
+        }
+    };
+    imp.into()
+}
 
-    match input.data {
-        syn::Data::Struct(v) => match v.fields {
-            syn::Fields::Named(v) => {
-                let mut has_loc = false;
-                let mut loc_id: Option<&Ident> = None;
-                for f in v.named.iter() {
-                    #[allow(unused_assignments)]
-                    if f.ident.as_ref().map(|id| id == "pos").unwrap_or(false) {
-                        has_loc = true;
-                        loc_id = f.ident.as_ref();
-                        break;
-                    }
-                    for attr in f.attrs.iter() {
-                        let path = &attr.path;
-                        if path.get_ident().map(|id| id == "pos").unwrap_or(false) {
-                            if !has_loc && loc_id.is_some() {
-                                return syn::Error::new(
-                                    Span::call_site(),
-                                    "Cannot have multiple pos",
-                                )
-                                .to_compile_error()
-                                .into();
-                            }
-                            loc_id = f.ident.as_ref();
-                            if loc_id.is_none() {
-                                return syn::Error::new(Span::call_site(), "Field has no name")
-                                    .to_compile_error()
-                                    .into();
-                            }
-                        }
-                    }
+/// The byte range is taken from the field selected like [`GetLoc`](derive.GetLoc.html),
+/// get Loc from it through `Indexed` with the line index of the text
+/// # Example
+/// ```
+/// # use srcpos_get::*;
+/// #[derive(GetByteRange)]
+/// struct Ident {
+///     #[loc]
+///     range: std::ops::Range<usize>,
+///     name: String,
+/// }
+///
+/// let index = LineIndex::new("let 变量 = 1;");
+/// let ident = Ident { range: 4..10, name: "变量".to_string() };
+/// assert_eq!(Indexed::new(&index, &ident).loc(), locof!(0, 4, 0, 6));
+/// ```
+#[proc_macro_derive(GetByteRange, attributes(loc))]
+pub fn derive_get_byte_range(item: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(item as DeriveInput);
+    let cfg = Config {
+        name: "loc",
+        trait_name: "GetByteRange",
+        merge: false,
+    };
+    let arms = match arms(&input, &cfg, |_, _, ids| {
+        let v = &ids[0];
+        quote! { #v.byte_range() }
+    }) {
+        Ok(v) => v,
+        Err(e) => return e.to_compile_error().into(),
+    };
+
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let imp = quote! {
+        impl #impl_generics ::srcpos_get::GetByteRange for #name #ty_generics #where_clause {
+            fn byte_range(&self) -> ::core::ops::Range<usize> {
+                use ::srcpos_get::GetByteRange;
+                match self {
+                    #(#arms),*
                 }
-                if loc_id.is_none() {
-                    return syn::Error::new(Span::call_site(), "Not found field pos")
-                        .to_compile_error()
-                        .into();
+            }
+        }
+    };
+    imp.into()
+}
+
+/// Needs the `proc-macro2` feature of srcpos_get
+/// # Example
+/// ```
+/// # use srcpos_get::*;
+/// use proc_macro2::{Ident, Span};
+/// use srcpos_get::ext::proc_macro2::GetSpan;
+///
+/// #[derive(GetSpan)]
+/// struct A {
+///     span: Span,
+/// }
+///
+/// #[derive(GetSpan)]
+/// enum B {
+///     A(A),
+///     B(u8, #[span] Ident),
+/// }
+///
+/// let a = B::A(A { span: Span::call_site() });
+/// let _ = syn::Error::new(a.span(), "unexpected");
+/// ```
+#[proc_macro_derive(GetSpan, attributes(span))]
+pub fn derive_get_span(item: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(item as DeriveInput);
+    let cfg = Config {
+        name: "span",
+        trait_name: "GetSpan",
+        merge: false,
+    };
+    let arms = match arms(&input, &cfg, |_, _, ids| {
+        let v = &ids[0];
+        quote! { #v.span() }
+    }) {
+        Ok(v) => v,
+        Err(e) => return e.to_compile_error().into(),
+    };
+
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let imp = quote! {
+        impl #impl_generics ::srcpos_get::ext::proc_macro2::GetSpan for #name #ty_generics #where_clause {
+            fn span(&self) -> ::srcpos_get::ext::proc_macro2::Span {
+                use ::srcpos_get::ext::proc_macro2::GetSpan;
+                match self {
+                    #(#arms),*
                 }
-                let loc_id = loc_id.unwrap();
-                let imp = quote! {
-                    impl #impl_generics ::srcpos_get::GetPos for #name #ty_generics #where_clause {
-                        fn pos(&self) -> ::srcpos_get::Pos {
-                            use ::srcpos_get::GetPos;
-                            self.#loc_id.pos()
-                        }
-                    }
-                };
-                return imp.into();
             }
-            syn::Fields::Unnamed(v) => {
-                let mut loc_id: Option<usize> = None;
-                for (i, f) in v.unnamed.iter().enumerate() {
-                    for attr in f.attrs.iter() {
-                        let path = &attr.path;
-                        if path.get_ident().map(|id| id == "pos").unwrap_or(false) {
-                            if loc_id.is_some() {
-                                return syn::Error::new(
-                                    Span::call_site(),
-                                    "Cannot have multiple pos",
-                                )
-                                .to_compile_error()
-                                .into();
-                            }
-                            loc_id = Some(i);
-                        }
-                    }
+        }
+    };
+    imp.into()
+}
+
+/// Visit every field, fields that have no Pos can be skipped by `#[visit_loc(skip)]`
+/// # Example
+/// ```
+/// # use srcpos_get::*;
+/// #[derive(VisitLocMut)]
+/// struct Ident {
+///     loc: Loc,
+///     name: String,
+/// }
+///
+/// #[derive(VisitLocMut)]
+/// enum Expr {
+///     Ident(Ident),
+///     Call {
+///         loc: Loc,
+///         callee: Box<Expr>,
+///         args: Vec<Expr>,
+///         #[visit_loc(skip)]
+///         id: std::any::TypeId,
+///     },
+///     Nil,
+/// }
+///
+/// let mut a = Ident { loc: locof!(1, 2, 1, 3), name: "a".to_string() };
+/// a.shift_loc(&TextEdit::insert(pos(0, 0), "\n"));
+/// assert_eq!(a.loc, locof!(2, 2, 2, 3));
+/// ```
+#[proc_macro_derive(VisitLocMut, attributes(visit_loc))]
+pub fn derive_visit_loc_mut(item: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(item as DeriveInput);
+    let cfg = Config {
+        name: "visit_loc",
+        trait_name: "VisitLocMut",
+        merge: false,
+    };
+    let arms = match all_arms(&input, &cfg, |ids| {
+        quote! {{ #(#ids.visit_loc_mut(f);)* }}
+    }) {
+        Ok(v) => v,
+        Err(e) => return e.to_compile_error().into(),
+    };
+
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let imp = quote! {
+        impl #impl_generics ::srcpos_get::VisitLocMut for #name #ty_generics #where_clause {
+            fn visit_loc_mut(&mut self, f: &mut dyn FnMut(&mut ::srcpos_get::Pos)) {
+                use ::srcpos_get::VisitLocMut;
+                match self {
+                    #(#arms),*
+                }
+            }
+        }
+    };
+    imp.into()
+}
+
+/// Recurse into the fields marked by `#[child]`, the type also needs [`GetLoc`](trait.GetLoc.html)
+/// # Example
+/// ```
+/// # use srcpos_get::*;
+/// #[derive(GetLoc, Locate)]
+/// struct Ident {
+///     loc: Loc,
+/// }
+///
+/// #[derive(GetLoc, Locate)]
+/// enum Expr {
+///     Ident(#[child] Ident),
+///     Call {
+///         loc: Loc,
+///         #[child]
+///         callee: Box<Expr>,
+///         #[child]
+///         args: Vec<Expr>,
+///     },
+/// }
+///
+/// let a = Expr::Call {
+///     loc: locof!(0, 0, 0, 4),
+///     callee: Box::new(Expr::Ident(Ident { loc: locof!(0, 0, 0, 1) })),
+///     args: vec![Expr::Ident(Ident { loc: locof!(0, 2, 0, 3) })],
+/// };
+/// let path = a.locate(pos(0, 2));
+/// assert_eq!(path.len(), 3);
+/// assert_eq!(path[2].loc(), locof!(0, 2, 0, 3));
+/// ```
+#[proc_macro_derive(Locate, attributes(child))]
+pub fn derive_locate(item: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(item as DeriveInput);
+    let cfg = Config {
+        name: "child",
+        trait_name: "Locate",
+        merge: false,
+    };
+    let arms = match marked_arms(&input, &cfg, |ids| {
+        quote! {{ let _ = false #(|| #ids.locate_into(pos, path))*; }}
+    }) {
+        Ok(v) => v,
+        Err(e) => return e.to_compile_error().into(),
+    };
+
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let imp = quote! {
+        impl #impl_generics ::srcpos_get::Locate for #name #ty_generics #where_clause {
+            fn locate_into<'__a>(
+                &'__a self,
+                pos: ::srcpos_get::Pos,
+                path: &mut ::std::vec::Vec<&'__a dyn ::srcpos_get::GetLoc>,
+            ) -> bool {
+                use ::srcpos_get::Locate;
+                if !::srcpos_get::loc_contains(::srcpos_get::GetLoc::loc(self), pos) {
+                    return false;
                 }
-                if v.unnamed.len() == 0 {
-                    return syn::Error::new(Span::call_site(), "There is nothing to get")
-                        .to_compile_error()
-                        .into();
+                path.push(self);
+                match self {
+                    #(#arms),*
                 }
-                if v.unnamed.len() == 1 && loc_id.is_none() {
-                    loc_id = Some(0);
+                true
+            }
+        }
+    };
+    imp.into()
+}
+
+/// The file is the field marked by `#[file]` or named `file`, the Loc is selected like [`GetLoc`](derive.GetLoc.html).  
+/// Without a file field, a single loc field is asked for its own FileLoc
+/// # Example
+/// ```
+/// # use srcpos_get::*;
+/// #[derive(GetFileLoc)]
+/// struct A {
+///     file: FileId,
+///     loc: Loc,
+/// }
+///
+/// #[derive(GetFileLoc)]
+/// struct B {
+///     #[file]
+///     a: FileId,
+///     #[loc(merge)]
+///     lhs: Loc,
+///     #[loc(merge)]
+///     rhs: Loc,
+/// }
+///
+/// #[derive(GetFileLoc)]
+/// enum C {
+///     A(A),
+///     B(u8, #[loc] FileLoc),
+/// }
+///
+/// let a = C::A(A { file: FileId(1), loc: locof!(0, 0, 0, 1) });
+/// assert_eq!(a.file_loc(), FileLoc::new(FileId(1), locof!(0, 0, 0, 1)));
+/// ```
+#[proc_macro_derive(GetFileLoc, attributes(file, loc))]
+pub fn derive_get_file_loc(item: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(item as DeriveInput);
+    let arms = match file_loc_arms(&input) {
+        Ok(v) => v,
+        Err(e) => return e.to_compile_error().into(),
+    };
+
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let imp = quote! {
+        impl #impl_generics ::srcpos_get::GetFileLoc for #name #ty_generics #where_clause {
+            fn file_loc(&self) -> ::srcpos_get::FileLoc {
+                #[allow(unused_imports)]
+                use ::srcpos_get::{GetFileLoc, GetLoc, TryGetLoc};
+                match self {
+                    #(#arms),*
                 }
-                if loc_id.is_none() {
-                    return syn::Error::new(Span::call_site(), "Not found field pos")
-                        .to_compile_error()
-                        .into();
+            }
+        }
+    };
+    imp.into()
+}
+
+fn file_loc_arms(input: &DeriveInput) -> syn::Result<Vec<proc_macro2::TokenStream>> {
+    let file_cfg = Config {
+        name: "file",
+        trait_name: "GetFileLoc",
+        merge: false,
+    };
+    let loc_cfg = Config {
+        name: "loc",
+        trait_name: "GetFileLoc",
+        merge: true,
+    };
+    let mut arms = vec![];
+    for target in targets(input, &loc_cfg)? {
+        let loc = select(&target, &loc_cfg)?;
+        let file = find(&target, &file_cfg)?;
+        let path = &target.path;
+        let body = match (file, &loc) {
+            (Some(Selection::One(file)), loc) if !loc.members().contains(&&file) => {
+                let mut members = vec![&file];
+                members.extend(loc.members());
+                let (pat, ids) = bind(&members);
+                let f = &ids[0];
+                let loc = get_loc(&loc_cfg, &target, loc, &ids[1..]);
+                quote! {
+                    #path #pat => ::srcpos_get::FileLoc::new(::core::clone::Clone::clone(#f), #loc)
                 }
-                let loc_id = loc_id.unwrap().to_string();
-                let loc_id = LitInt::new(loc_id.as_str(), Span::call_site());
-                let imp = quote! {
-                    impl #impl_generics ::srcpos_get::GetPos for #name #ty_generics #where_clause {
-                        fn pos(&self) -> ::srcpos_get::Pos {
-                            use ::srcpos_get::GetPos;
-                            self.#loc_id.pos()
-                        }
-                    }
-                };
-                return imp.into();
             }
-            syn::Fields::Unit => {
-                return syn::Error::new(Span::call_site(), "There is nothing to get")
-                    .to_compile_error()
-                    .into();
+            (_, Selection::One(_)) => {
+                let (pat, ids) = bind(&loc.members());
+                let v = &ids[0];
+                quote! { #path #pat => #v.file_loc() }
             }
-        },
-        syn::Data::Enum(v) => {
-            let mut vimps = vec![];
-            if v.variants.len() == 0 {
-                return syn::Error::new(Span::call_site(), "There is nothing to get")
-                    .to_compile_error()
-                    .into();
+            _ => return Err(file_cfg.error(target.span, "Not found field file")),
+        };
+        arms.push(quote_spanned! { target.span => #body });
+    }
+    Ok(arms)
+}
+
+/// Pick the serde representation of Loc fields, put it before `#[derive(Serialize, Deserialize)]`.  
+/// Fields marked by `#[serde_loc(structure)]`, `#[serde_loc(compact)]` or `#[serde_loc(string)]`
+/// are serialized with the module of the same name in `srcpos_get::ext::serde`,
+/// needs the `serde` feature of srcpos_get
+/// # Example
+/// ```
+/// # use srcpos_get::*;
+/// use serde::Serialize;
+///
+/// #[serde_loc]
+/// #[derive(Serialize)]
+/// enum Expr {
+///     Ident(String, #[serde_loc(compact)] Loc),
+///     Call {
+///         #[serde_loc(string)]
+///         loc: Loc,
+///         args: Vec<Expr>,
+///     },
+/// }
+///
+/// let a = Expr::Ident("a".to_string(), locof!(0, 1, 0, 2));
+/// assert_eq!(serde_json::to_string(&a).unwrap(), r#"{"Ident":["a",[0,1,0,2]]}"#);
+/// ```
+#[proc_macro_attribute]
+pub fn serde_loc(attr: TokenStream, item: TokenStream) -> TokenStream {
+    let attr = proc_macro2::TokenStream::from(attr);
+    if !attr.is_empty() {
+        return syn::Error::new_spanned(attr, "[serde_loc] Does not take arguments")
+            .to_compile_error()
+            .into();
+    }
+    let mut input = parse_macro_input!(item as DeriveInput);
+    if let Err(e) = serde_loc_fields(&mut input) {
+        return e.to_compile_error().into();
+    }
+    quote!(#input).into()
+}
+
+/// Replace `#[serde_loc(..)]` of every field by `#[serde(with = "..")]`
+fn serde_loc_fields(input: &mut DeriveInput) -> syn::Result<()> {
+    let fields = match &mut input.data {
+        syn::Data::Struct(v) => vec![&mut v.fields],
+        syn::Data::Enum(v) => v.variants.iter_mut().map(|v| &mut v.fields).collect(),
+        syn::Data::Union(_) => {
+            return Err(syn::Error::new(
+                input.ident.span(),
+                "[serde_loc] Does not support union",
+            ))
+        }
+    };
+    for field in fields.into_iter().flat_map(|f| f.iter_mut()) {
+        let mut repr: Option<Ident> = None;
+        for attr in field.attrs.iter().filter(|a| a.path.is_ident("serde_loc")) {
+            let id: Ident = attr.parse_args()?;
+            if !["structure", "compact", "string"].iter().any(|n| id == n) {
+                return Err(syn::Error::new(
+                    id.span(),
+                    "[serde_loc] Unknown representation, expected `structure`, `compact` or `string`",
+                ));
             }
-            for variant in v.variants.iter() {
-                match &variant.fields {
-                    syn::Fields::Named(v) => {
-                        let mut has_loc = false;
-                        let mut loc_id: Option<&Ident> = None;
-                        for f in v.named.iter() {
-                            #[allow(unused_assignments)]
-                            if f.ident.as_ref().map(|id| id == "pos").unwrap_or(false) {
-                                has_loc = true;
-                                loc_id = f.ident.as_ref();
-                                break;
-                            }
-                            for attr in f.attrs.iter() {
-                                let path = &attr.path;
-                                if path.get_ident().map(|id| id == "pos").unwrap_or(false) {
-                                    if !has_loc && loc_id.is_some() {
-                                        return syn::Error::new(
-                                            variant.ident.span(),
-                                            "[GetPos] Cannot have multiple pos",
-                                        )
-                                        .to_compile_error()
-                                        .into();
-                                    }
-                                    loc_id = f.ident.as_ref();
-                                    if loc_id.is_none() {
-                                        return syn::Error::new(
-                                            variant.ident.span(),
-                                            "[GetPos] Field has no name",
-                                        )
-                                        .to_compile_error()
-                                        .into();
-                                    }
-                                }
-                            }
-                        }
-                        if loc_id.is_none() {
-                            return syn::Error::new(
-                                variant.ident.span(),
-                                "[GetPos] Not found field pos",
-                            )
-                            .to_compile_error()
-                            .into();
-                        }
-                        let loc_id = loc_id.unwrap();
-                        let vname = &variant.ident;
-                        let imp = quote_spanned! { variant.ident.span() => Self::#vname { #loc_id, .. } => #loc_id.pos() };
-                        vimps.push(imp);
-                    }
-                    syn::Fields::Unnamed(v) => {
-                        let mut loc_id: Option<usize> = None;
-                        for (i, f) in v.unnamed.iter().enumerate() {
-                            for attr in f.attrs.iter() {
-                                let path = &attr.path;
-                                if path.get_ident().map(|id| id == "pos").unwrap_or(false) {
-                                    if loc_id.is_some() {
-                                        return syn::Error::new(
-                                            variant.ident.span(),
-                                            "[GetPos] Cannot have multiple pos",
-                                        )
-                                        .to_compile_error()
-                                        .into();
-                                    }
-                                    loc_id = Some(i);
-                                }
-                            }
-                        }
-                        if v.unnamed.len() == 0 {
-                            return syn::Error::new(
-                                variant.ident.span(),
-                                "[GetPos] There is nothing to get",
-                            )
-                            .to_compile_error()
-                            .into();
-                        }
-                        if v.unnamed.len() == 1 && loc_id.is_none() {
-                            loc_id = Some(0);
-                        }
-                        if loc_id.is_none() {
-                            return syn::Error::new(
-                                variant.ident.span(),
-                                "[GetPos] Not found field pos",
-                            )
-                            .to_compile_error()
-                            .into();
-                        }
-                        let loc_id = loc_id.unwrap();
-                        let ids = (0..(variant.fields.len())).into_iter().map(|i| {
-                            if i != loc_id {
-                                format_ident!("_")
-                            } else {
-                                format_ident!("v{}", i)
-                            }
-                        });
-                        let loc_id = format_ident!("v{}", loc_id);
-                        let vname = &variant.ident;
-                        let imp = quote_spanned! { variant.ident.span() => Self::#vname(#(#ids),*) => #loc_id.pos() };
-                        vimps.push(imp);
-                    }
-                    syn::Fields::Unit => {
-                        return syn::Error::new(
-                            variant.ident.span(),
-                            "[GetPos] There is nothing to get",
-                        )
-                        .to_compile_error()
-                        .into();
-                    }
-                }
+            if repr.is_some() {
+                return Err(syn::Error::new(
+                    id.span(),
+                    "[serde_loc] Duplicate representation",
+                ));
             }
-            let imp = quote! {
-                impl #impl_generics ::srcpos_get::GetPos for #name #ty_generics #where_clause {
-                    fn pos(&self) -> ::srcpos_get::Pos {
-                        use ::srcpos_get::GetPos;
-                        match self {
-                            #(#vimps),*
-                        }
-                    }
-                }
-            };
-            return imp.into();
+            repr = Some(id);
         }
-        syn::Data::Union(_) => {
-            return syn::Error::new(Span::call_site(), "Does not support union")
-                .to_compile_error()
-                .into();
+        if let Some(repr) = repr {
+            let with = format!("::srcpos_get::ext::serde::{}", repr);
+            field.attrs.retain(|a| !a.path.is_ident("serde_loc"));
+            field.attrs.push(syn::parse_quote!(#[serde(with = #with)]));
         }
     }
+    Ok(())
 }
@@ -0,0 +1,370 @@
+use proc_macro2::{Span, TokenStream};
+use quote::{format_ident, quote, quote_spanned};
+use syn::parse::{Parse, ParseStream};
+use syn::spanned::Spanned;
+use syn::{Attribute, DeriveInput, Field, Fields, Ident, Member, Token, Type};
+
+/// What a derive is looking for
+pub(crate) struct Config {
+    /// Attribute and default field name, `loc` or `pos`
+    pub name: &'static str,
+    /// Trait name used in error messages
+    pub trait_name: &'static str,
+    /// Allow `#[loc(merge)]` and `#[loc(from = a, to = b)]`
+    pub merge: bool,
+}
+
+impl Config {
+    pub fn error(&self, span: Span, msg: &str) -> syn::Error {
+        syn::Error::new(span, format!("[{}] {}", self.trait_name, msg))
+    }
+}
+
+/// A struct or an enum variant
+pub(crate) struct Target<'a> {
+    /// `Self` or `Self::Variant`
+    pub path: TokenStream,
+    pub fields: &'a Fields,
+    pub attrs: &'a [Attribute],
+    pub span: Span,
+}
+
+/// Collect the struct or every variant of the enum
+pub(crate) fn targets<'a>(input: &'a DeriveInput, cfg: &Config) -> syn::Result<Vec<Target<'a>>> {
+    match &input.data {
+        syn::Data::Struct(v) => Ok(vec![Target {
+            path: quote! { Self },
+            fields: &v.fields,
+            attrs: &input.attrs,
+            span: Span::call_site(),
+        }]),
+        syn::Data::Enum(v) => {
+            if v.variants.is_empty() {
+                return Err(cfg.error(Span::call_site(), "There is nothing to get"));
+            }
+            if let Some(attr) = input.attrs.iter().find(|a| is_attr(a, cfg.name)) {
+                let msg = if cfg.merge {
+                    format!("Put #[{}(from = .., to = ..)] on the variant", cfg.name)
+                } else {
+                    "Does not support from / to".to_string()
+                };
+                return Err(cfg.error(attr.path.span(), &msg));
+            }
+            Ok(v.variants
+                .iter()
+                .map(|variant| {
+                    let vname = &variant.ident;
+                    Target {
+                        path: quote! { Self::#vname },
+                        fields: &variant.fields,
+                        attrs: &variant.attrs,
+                        span: variant.ident.span(),
+                    }
+                })
+                .collect())
+        }
+        syn::Data::Union(_) => Err(cfg.error(Span::call_site(), "Does not support union")),
+    }
+}
+
+/// Which fields the span is calculated from
+pub(crate) enum Selection {
+    /// A single field
+    One(Member),
+    /// Covering span of all `#[loc(merge)]` fields
+    Merge(Vec<Member>),
+    /// From the start of the first field to the end of the second one
+    FromTo(Member, Member),
+}
+
+impl Selection {
+    pub fn members(&self) -> Vec<&Member> {
+        match self {
+            Selection::One(m) => vec![m],
+            Selection::Merge(ms) => ms.iter().collect(),
+            Selection::FromTo(a, b) => vec![a, b],
+        }
+    }
+}
+
+enum FieldAttr {
+    Plain,
+    Merge,
+}
+
+impl Parse for FieldAttr {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let id: Ident = input.parse()?;
+        if id == "merge" {
+            Ok(FieldAttr::Merge)
+        } else {
+            Err(syn::Error::new(
+                id.span(),
+                "Unknown option, expected `merge`",
+            ))
+        }
+    }
+}
+
+struct FromTo {
+    from: Member,
+    to: Member,
+}
+
+impl Parse for FromTo {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let mut from = None;
+        let mut to = None;
+        while !input.is_empty() {
+            let key: Ident = input.parse()?;
+            input.parse::<Token![=]>()?;
+            let value: Member = input.parse()?;
+            if key == "from" {
+                from = Some(value);
+            } else if key == "to" {
+                to = Some(value);
+            } else {
+                return Err(syn::Error::new(
+                    key.span(),
+                    "Unknown option, expected `from` or `to`",
+                ));
+            }
+            if input.is_empty() {
+                break;
+            }
+            input.parse::<Token![,]>()?;
+        }
+        match (from, to) {
+            (Some(from), Some(to)) => Ok(FromTo { from, to }),
+            _ => Err(input.error("Both `from` and `to` are required")),
+        }
+    }
+}
+
+fn is_attr(attr: &Attribute, name: &str) -> bool {
+    attr.path.get_ident().map(|id| id == name).unwrap_or(false)
+}
+
+/// The field of a member
+pub(crate) fn field<'a>(fields: &'a Fields, member: &Member) -> Option<&'a Field> {
+    fields
+        .iter()
+        .enumerate()
+        .find_map(|(i, f)| match (member, &f.ident) {
+            (Member::Named(m), Some(id)) if m == id => Some(f),
+            (Member::Unnamed(m), None) if m.index as usize == i => Some(f),
+            _ => None,
+        })
+}
+
+/// `Option`, `Vec`, an array or a slice, which may have no Loc
+pub(crate) fn may_be_empty(ty: &Type) -> bool {
+    match ty {
+        Type::Slice(_) | Type::Array(_) => true,
+        Type::Reference(v) => may_be_empty(&v.elem),
+        Type::Paren(v) => may_be_empty(&v.elem),
+        Type::Group(v) => may_be_empty(&v.elem),
+        Type::Path(v) => v
+            .path
+            .segments
+            .last()
+            .map(|s| s.ident == "Option" || s.ident == "Vec")
+            .unwrap_or(false),
+        _ => false,
+    }
+}
+
+fn member_of(i: usize, f: &Field) -> Member {
+    match &f.ident {
+        Some(id) => Member::Named(id.clone()),
+        None => Member::Unnamed(syn::Index::from(i)),
+    }
+}
+
+/// Find the fields marked by `#[loc]`, or the field named `loc`
+pub(crate) fn select(target: &Target, cfg: &Config) -> syn::Result<Selection> {
+    match find(target, cfg)? {
+        Some(sel) => Ok(sel),
+        None => Err(cfg.error(target.span, &format!("Not found field {}", cfg.name))),
+    }
+}
+
+/// Like [`select`], but `None` if not found
+pub(crate) fn find(target: &Target, cfg: &Config) -> syn::Result<Option<Selection>> {
+    let fields = target.fields;
+    if fields.is_empty() {
+        return Err(cfg.error(target.span, "There is nothing to get"));
+    }
+
+    if let Some(attr) = target.attrs.iter().find(|a| is_attr(a, cfg.name)) {
+        if !cfg.merge {
+            return Err(cfg.error(target.span, "Does not support from / to"));
+        }
+        let FromTo { from, to } = attr.parse_args()?;
+        for m in [&from, &to].iter() {
+            if field(fields, m).is_none() {
+                return Err(cfg.error(target.span, "Not found field in from / to"));
+            }
+        }
+        if from == to {
+            return Ok(Some(Selection::One(from)));
+        }
+        return Ok(Some(Selection::FromTo(from, to)));
+    }
+
+    let mut plain: Option<Member> = None;
+    let mut merge: Vec<Member> = vec![];
+    let mut named: Option<Member> = None;
+    for (i, f) in fields.iter().enumerate() {
+        if f.ident.as_ref().map(|id| id == cfg.name).unwrap_or(false) {
+            named = Some(member_of(i, f));
+        }
+        for attr in f.attrs.iter().filter(|a| is_attr(a, cfg.name)) {
+            let kind = if attr.tokens.is_empty() {
+                FieldAttr::Plain
+            } else {
+                attr.parse_args()?
+            };
+            match kind {
+                FieldAttr::Plain => {
+                    if plain.is_some() {
+                        return Err(
+                            cfg.error(target.span, &format!("Cannot have multiple {}", cfg.name))
+                        );
+                    }
+                    plain = Some(member_of(i, f));
+                }
+                FieldAttr::Merge if cfg.merge => merge.push(member_of(i, f)),
+                FieldAttr::Merge => {
+                    return Err(cfg.error(target.span, "Does not support merge"));
+                }
+            }
+        }
+    }
+
+    if !merge.is_empty() {
+        if plain.is_some() {
+            return Err(cfg.error(
+                target.span,
+                &format!("Cannot mix #[{0}] and #[{0}(merge)]", cfg.name),
+            ));
+        }
+        return Ok(Some(Selection::Merge(merge)));
+    }
+    if let Some(m) = named.or(plain) {
+        return Ok(Some(Selection::One(m)));
+    }
+    if let Fields::Unnamed(v) = fields {
+        if v.unnamed.len() == 1 {
+            return Ok(Some(Selection::One(Member::Unnamed(syn::Index::from(0)))));
+        }
+    }
+    Ok(None)
+}
+
+/// Build `{ a: __v0, 1: __v1, .. }` binding the given members
+pub(crate) fn bind(members: &[&Member]) -> (TokenStream, Vec<Ident>) {
+    let mut pats = vec![];
+    let mut ids = vec![];
+    for (i, m) in members.iter().enumerate() {
+        let id = format_ident!("__v{}", i);
+        pats.push(quote! { #m: #id });
+        ids.push(id);
+    }
+    (quote! { { #(#pats,)* .. } }, ids)
+}
+
+/// Build one match arm per target, `f` makes the arm body from the bound fields
+pub(crate) fn arms(
+    input: &DeriveInput,
+    cfg: &Config,
+    f: impl Fn(&Target, &Selection, &[Ident]) -> TokenStream,
+) -> syn::Result<Vec<TokenStream>> {
+    let mut arms = vec![];
+    for target in targets(input, cfg)? {
+        let sel = select(&target, cfg)?;
+        let (pat, ids) = bind(&sel.members());
+        let path = &target.path;
+        let body = f(&target, &sel, &ids);
+        arms.push(quote_spanned! { target.span => #path #pat => #body });
+    }
+    Ok(arms)
+}
+
+struct Skip;
+
+impl Parse for Skip {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let id: Ident = input.parse()?;
+        if id == "skip" {
+            Ok(Skip)
+        } else {
+            Err(syn::Error::new(
+                id.span(),
+                "Unknown option, expected `skip`",
+            ))
+        }
+    }
+}
+
+/// Build one match arm per target binding every field not marked by `#[<name>(skip)]`
+pub(crate) fn all_arms(
+    input: &DeriveInput,
+    cfg: &Config,
+    f: impl Fn(&[Ident]) -> TokenStream,
+) -> syn::Result<Vec<TokenStream>> {
+    field_arms(
+        input,
+        cfg,
+        |field| match field.attrs.iter().find(|a| is_attr(a, cfg.name)) {
+            Some(attr) => {
+                let Skip = attr.parse_args()?;
+                Ok(false)
+            }
+            None => Ok(true),
+        },
+        f,
+    )
+}
+
+/// Build one match arm per target binding every field marked by `#[<name>]`
+pub(crate) fn marked_arms(
+    input: &DeriveInput,
+    cfg: &Config,
+    f: impl Fn(&[Ident]) -> TokenStream,
+) -> syn::Result<Vec<TokenStream>> {
+    field_arms(
+        input,
+        cfg,
+        |field| match field.attrs.iter().find(|a| is_attr(a, cfg.name)) {
+            Some(attr) if !attr.tokens.is_empty() => {
+                Err(cfg.error(attr.path.span(), "Does not support options"))
+            }
+            Some(_) => Ok(true),
+            None => Ok(false),
+        },
+        f,
+    )
+}
+
+fn field_arms(
+    input: &DeriveInput,
+    cfg: &Config,
+    keep: impl Fn(&Field) -> syn::Result<bool>,
+    f: impl Fn(&[Ident]) -> TokenStream,
+) -> syn::Result<Vec<TokenStream>> {
+    let mut arms = vec![];
+    for target in targets(input, cfg)? {
+        let mut members = vec![];
+        for (i, field) in target.fields.iter().enumerate() {
+            if keep(field)? {
+                members.push(member_of(i, field));
+            }
+        }
+        let (pat, ids) = bind(&members.iter().collect::<Vec<_>>());
+        let path = &target.path;
+        let body = f(&ids);
+        arms.push(quote_spanned! { target.span => #path #pat => #body });
+    }
+    Ok(arms)
+}
@@ -0,0 +1,77 @@
+#![allow(dead_code)]
+
+use srcpos::*;
+use srcpos_get::*;
+
+#[derive(GetLoc, GetFileLoc)]
+struct A {
+    file: FileId,
+    loc: Loc,
+}
+
+#[test]
+fn test_a() {
+    let a = A {
+        file: FileId(1),
+        loc: locof!(0, 0, 0, 1),
+    };
+    let v = a.file_loc();
+    assert_eq!(v, FileLoc::new(FileId(1), locof!(0, 0, 0, 1)));
+    assert_eq!(a.loc(), locof!(0, 0, 0, 1));
+}
+
+#[derive(GetFileLoc)]
+#[loc(from = 1, to = 2)]
+struct B(#[file] FileId, Loc, Loc);
+
+#[test]
+fn test_b() {
+    let b = B(FileId(2), locof!(0, 0, 0, 1), locof!(1, 0, 1, 1));
+    let v = b.file_loc();
+    assert_eq!(v, FileLoc::new(FileId(2), locof!(0, 0, 1, 1)));
+}
+
+#[derive(GetLoc, GetFileLoc)]
+struct C(FileLoc);
+
+#[test]
+fn test_c() {
+    let c = C(FileLoc::new(FileId(3), locof!(0, 0, 0, 1)));
+    let v = c.file_loc();
+    assert_eq!(v, FileLoc::new(FileId(3), locof!(0, 0, 0, 1)));
+    assert_eq!(c.loc(), locof!(0, 0, 0, 1));
+}
+
+#[derive(GetFileLoc)]
+enum D {
+    A(A),
+    B {
+        #[file]
+        f: FileId,
+        #[loc(merge)]
+        lhs: Loc,
+        #[loc(merge)]
+        rhs: Option<Loc>,
+    },
+}
+
+#[test]
+fn test_d_a() {
+    let d = D::A(A {
+        file: FileId(1),
+        loc: locof!(0, 0, 0, 1),
+    });
+    let v = d.file_loc();
+    assert_eq!(v, FileLoc::new(FileId(1), locof!(0, 0, 0, 1)));
+}
+
+#[test]
+fn test_d_b() {
+    let d = D::B {
+        f: FileId(4),
+        lhs: locof!(0, 0, 0, 1),
+        rhs: Some(locof!(0, 3, 0, 4)),
+    };
+    let v = d.file_loc();
+    assert_eq!(v, FileLoc::new(FileId(4), locof!(0, 0, 0, 4)));
+}
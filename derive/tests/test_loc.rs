@@ -41,6 +41,7 @@ fn test_c() {
 }
 
 #[derive(GetLoc)]
+#[allow(dead_code)]
 struct D(u8, #[loc] Loc);
 
 #[test]
@@ -51,6 +52,7 @@ fn test_d() {
 }
 
 #[derive(GetLoc)]
+#[allow(dead_code)]
 enum E {
     A(Loc),
     B(u8, #[loc] Loc),
@@ -106,3 +108,168 @@ fn test_f() {
     let v = f.loc();
     assert_eq!(v, locof!(0, 0, 0, 0));
 }
+
+#[derive(GetLoc)]
+struct G {
+    #[loc(merge)]
+    lhs: Loc,
+    _op: u8,
+    #[loc(merge)]
+    rhs: Loc,
+}
+
+#[test]
+fn test_g() {
+    let g = G {
+        lhs: locof!(0, 0, 0, 1),
+        _op: 0,
+        rhs: locof!(1, 2, 1, 3),
+    };
+    let v = g.loc();
+    assert_eq!(v, locof!(0, 0, 1, 3));
+}
+
+#[derive(GetLoc)]
+#[loc(from = 0, to = 2)]
+#[allow(dead_code)]
+struct H(A, u8, C);
+
+#[test]
+fn test_h() {
+    let h = H(
+        A {
+            loc: locof!(0, 0, 0, 1),
+        },
+        0,
+        C(locof!(0, 4, 0, 5)),
+    );
+    let v = h.loc();
+    assert_eq!(v, locof!(0, 0, 0, 5));
+}
+
+#[derive(GetLoc)]
+enum I {
+    #[loc(from = lhs, to = rhs)]
+    A {
+        lhs: Loc,
+        _op: u8,
+        rhs: Loc,
+    },
+    B(#[loc(merge)] Loc, #[loc(merge)] Loc),
+}
+
+#[test]
+fn test_i_a() {
+    let i = I::A {
+        lhs: locof!(0, 0, 0, 1),
+        _op: 0,
+        rhs: locof!(0, 4, 0, 5),
+    };
+    let v = i.loc();
+    assert_eq!(v, locof!(0, 0, 0, 5));
+}
+
+#[test]
+fn test_i_b() {
+    let i = I::B(locof!(0, 4, 0, 5), locof!(0, 0, 0, 1));
+    let v = i.loc();
+    assert_eq!(v, locof!(0, 0, 0, 5));
+}
+
+#[derive(GetLoc)]
+struct J {
+    #[loc(merge)]
+    callee: Loc,
+    #[loc(merge)]
+    args: Vec<Loc>,
+    #[loc(merge)]
+    rest: Option<Loc>,
+}
+
+#[test]
+fn test_j() {
+    let j = J {
+        callee: locof!(0, 0, 0, 1),
+        args: vec![locof!(0, 2, 0, 3), locof!(0, 5, 0, 6)],
+        rest: None,
+    };
+    let v = j.loc();
+    assert_eq!(v, locof!(0, 0, 0, 6));
+}
+
+#[derive(TryGetLoc)]
+struct K {
+    #[loc]
+    else_branch: Option<Loc>,
+}
+
+#[test]
+fn test_k() {
+    let k = K { else_branch: None };
+    let v = k.try_loc();
+    assert_eq!(v, None);
+    let k = K {
+        else_branch: Some(locof!(0, 0, 0, 1)),
+    };
+    let v = k.try_loc();
+    assert_eq!(v, Some(locof!(0, 0, 0, 1)));
+}
+
+#[derive(TryGetLoc)]
+enum L {
+    A(Vec<Loc>),
+    #[loc(from = cond, to = else_branch)]
+    B {
+        cond: Loc,
+        else_branch: Option<Loc>,
+    },
+}
+
+#[test]
+fn test_l_a() {
+    let l = L::A(vec![]);
+    let v = l.try_loc();
+    assert_eq!(v, None);
+}
+
+#[test]
+fn test_l_b() {
+    let l = L::B {
+        cond: locof!(0, 0, 0, 1),
+        else_branch: None,
+    };
+    let v = l.try_loc();
+    assert_eq!(v, Some(locof!(0, 0, 0, 1)));
+}
+
+#[derive(GetLoc)]
+#[loc(from = cond, to = else_branch)]
+struct M {
+    cond: Loc,
+    else_branch: Option<Loc>,
+}
+
+#[test]
+fn test_m() {
+    let mut m = M {
+        cond: locof!(0, 3, 0, 4),
+        else_branch: None,
+    };
+    assert_eq!(m.loc(), locof!(0, 3, 0, 4));
+    m.else_branch = Some(locof!(1, 0, 1, 5));
+    assert_eq!(m.loc(), locof!(0, 3, 1, 5));
+}
+
+#[derive(TryGetLoc)]
+struct N {
+    #[loc]
+    args: Vec<Option<Loc>>,
+}
+
+#[test]
+fn test_n() {
+    let n = N {
+        args: vec![None, Some(locof!(0, 1, 0, 2))],
+    };
+    assert_eq!(n.try_loc(), Some(locof!(0, 1, 0, 2)));
+}
@@ -0,0 +1,71 @@
+#![allow(dead_code)]
+
+use srcpos::*;
+use srcpos_get::*;
+
+#[derive(GetLoc, Locate)]
+struct A {
+    loc: Loc,
+}
+
+#[derive(GetLoc, Locate)]
+struct B {
+    #[loc(merge)]
+    #[child]
+    lhs: A,
+    #[loc(merge)]
+    #[child]
+    rhs: Option<Box<B>>,
+}
+
+#[derive(GetLoc, Locate)]
+enum C {
+    A(#[child] A),
+    B {
+        loc: Loc,
+        #[child]
+        items: Vec<C>,
+    },
+}
+
+#[test]
+fn test_b() {
+    let b = B {
+        lhs: A {
+            loc: locof!(0, 0, 0, 1),
+        },
+        rhs: Some(Box::new(B {
+            lhs: A {
+                loc: locof!(0, 4, 0, 5),
+            },
+            rhs: None,
+        })),
+    };
+    let path = b.locate(pos(0, 4));
+    let locs: Vec<_> = path.iter().map(|v| v.loc()).collect();
+    assert_eq!(
+        locs,
+        vec![locof!(0, 0, 0, 5), locof!(0, 4, 0, 5), locof!(0, 4, 0, 5)]
+    );
+    assert!(b.locate(pos(0, 2)).len() == 1);
+    assert!(b.node_at(pos(1, 0)).is_none());
+}
+
+#[test]
+fn test_c() {
+    let c = C::B {
+        loc: locof!(0, 0, 1, 0),
+        items: vec![
+            C::A(A {
+                loc: locof!(0, 1, 0, 2),
+            }),
+            C::A(A {
+                loc: locof!(0, 3, 0, 4),
+            }),
+        ],
+    };
+    let v = c.node_at(pos(0, 3)).map(|v| v.loc());
+    assert_eq!(v, Some(locof!(0, 3, 0, 4)));
+    let v = c.node_at(pos(0, 2)).map(|v| v.loc());
+    assert_eq!(v, Some(locof!(0, 0, 1, 0)));
+}
@@ -37,6 +37,7 @@ fn test_c() {
 }
 
 #[derive(GetPos)]
+#[allow(dead_code)]
 struct D(u8, #[pos] Pos);
 
 #[test]
@@ -47,6 +48,7 @@ fn test_d() {
 }
 
 #[derive(GetPos)]
+#[allow(dead_code)]
 enum E {
     A(Pos),
     B(u8, #[pos] Pos),
@@ -0,0 +1,57 @@
+#![allow(dead_code)]
+
+use serde::{Deserialize, Serialize};
+use srcpos::*;
+use srcpos_get::*;
+
+#[serde_loc]
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+struct A {
+    #[serde_loc(structure)]
+    a: Loc,
+    #[serde_loc(compact)]
+    b: Loc,
+    #[serde_loc(string)]
+    c: Pos,
+    #[serde_loc(compact)]
+    d: FileLoc,
+    #[serde_loc(string)]
+    e: FileLoc,
+    f: Loc,
+}
+
+#[test]
+fn test_a() {
+    let loc = locof!(3, 5, 4, 1);
+    let a = A {
+        a: loc,
+        b: loc,
+        c: pos(3, 5),
+        d: FileLoc::new(FileId(2), loc),
+        e: FileLoc::new(FileId(2), loc),
+        f: loc,
+    };
+    let json = serde_json::to_string(&a).unwrap();
+    assert_eq!(
+        json,
+        concat!(
+            r#"{"a":{"from":{"line":3,"column":5},"to":{"line":4,"column":1}},"#,
+            r##""b":[3,5,4,1],"c":"3:5","d":[2,3,5,4,1],"e":"#2 3:5-4:1","##,
+            r#""f":{"from":{"line":3,"column":5},"to":{"line":4,"column":1}}}"#
+        )
+    );
+    assert_eq!(serde_json::from_str::<A>(&json).unwrap(), a);
+}
+
+#[serde_loc]
+#[derive(Deserialize, Debug)]
+struct B(#[serde_loc(compact)] Loc, #[serde_loc(string)] Loc);
+
+#[test]
+fn test_b() {
+    let b: B = serde_json::from_str(r#"[[0,1,2,3],"4:5-6:7"]"#).unwrap();
+    assert_eq!(b.0, locof!(0, 1, 2, 3));
+    assert_eq!(b.1, locof!(4, 5, 6, 7));
+    assert!(serde_json::from_str::<B>(r#"[[0,1,2],"4:5-6:7"]"#).is_err());
+    assert!(serde_json::from_str::<B>(r#"[[0,1,2,3],"4:5"]"#).is_err());
+}
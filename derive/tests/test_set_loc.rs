@@ -0,0 +1,66 @@
+#![allow(dead_code)]
+
+use srcpos::*;
+use srcpos_get::*;
+
+#[derive(GetLoc, GetLocMut, SetLoc)]
+struct A {
+    loc: Loc,
+}
+
+#[test]
+fn test_a() {
+    let mut a = A {
+        loc: locof!(0, 0, 0, 0),
+    };
+    a.set_loc(locof!(1, 2, 3, 4));
+    assert_eq!(a.loc(), locof!(1, 2, 3, 4));
+    *a.loc_mut() = locof!(0, 1, 0, 2);
+    assert_eq!(a.loc(), locof!(0, 1, 0, 2));
+}
+
+#[derive(GetLoc, GetLocMut, SetLoc)]
+struct B(u8, #[loc] Box<A>);
+
+#[test]
+fn test_b() {
+    let mut b = B(
+        0,
+        Box::new(A {
+            loc: locof!(0, 0, 0, 0),
+        }),
+    );
+    b.set_loc(locof!(1, 2, 3, 4));
+    assert_eq!(b.loc(), locof!(1, 2, 3, 4));
+    *b.loc_mut() = locof!(0, 1, 0, 2);
+    assert_eq!(b.loc(), locof!(0, 1, 0, 2));
+}
+
+#[derive(GetLoc, GetLocMut, SetLoc)]
+enum C {
+    A(Loc),
+    B {
+        #[loc]
+        a: A,
+        _b: u8,
+    },
+}
+
+#[test]
+fn test_c_a() {
+    let mut c = C::A(locof!(0, 0, 0, 0));
+    c.set_loc(locof!(1, 2, 3, 4));
+    assert_eq!(c.loc(), locof!(1, 2, 3, 4));
+}
+
+#[test]
+fn test_c_b() {
+    let mut c = C::B {
+        a: A {
+            loc: locof!(0, 0, 0, 0),
+        },
+        _b: 0,
+    };
+    *c.loc_mut() = locof!(1, 2, 3, 4);
+    assert_eq!(c.loc(), locof!(1, 2, 3, 4));
+}
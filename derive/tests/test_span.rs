@@ -0,0 +1,50 @@
+#![allow(dead_code)]
+
+use proc_macro2::{Ident, Span, TokenStream, TokenTree};
+use srcpos::*;
+use srcpos_get::ext::proc_macro2::GetSpan;
+use srcpos_get::*;
+
+#[derive(GetLoc, GetSpan)]
+struct A {
+    #[loc]
+    span: Span,
+}
+
+#[derive(GetSpan)]
+enum Node {
+    A(A),
+    Name(u8, #[span] Ident),
+    Boxed {
+        #[span]
+        a: Box<A>,
+    },
+}
+
+fn ident(source: &str) -> Ident {
+    match source.parse::<TokenStream>().unwrap().into_iter().last() {
+        Some(TokenTree::Ident(v)) => v,
+        _ => unreachable!(),
+    }
+}
+
+#[test]
+fn test_a() {
+    let a = A {
+        span: ident("x\n  abc").span(),
+    };
+    assert_eq!(a.loc(), locof!(1, 2, 1, 5));
+    assert_eq!(a.span().start().line, 2);
+}
+
+#[test]
+fn test_b() {
+    let b = Node::Name(0, ident("\n\nx"));
+    assert_eq!(b.span().loc(), locof!(2, 0, 2, 1));
+    let c = Node::Boxed {
+        a: Box::new(A {
+            span: ident("ab").span(),
+        }),
+    };
+    assert_eq!(c.span().loc(), locof!(0, 0, 0, 2));
+}
@@ -0,0 +1,64 @@
+#![allow(dead_code)]
+
+use srcpos::*;
+use srcpos_get::*;
+
+#[derive(VisitLocMut)]
+struct A {
+    loc: Loc,
+    name: String,
+}
+
+#[derive(VisitLocMut)]
+enum B {
+    A(A),
+    Call {
+        loc: Loc,
+        args: Vec<B>,
+        rest: Option<Box<B>>,
+        #[visit_loc(skip)]
+        id: std::any::TypeId,
+    },
+    C(Pos, u8),
+    D,
+}
+
+#[test]
+fn test_a() {
+    let mut a = A {
+        loc: locof!(0, 0, 0, 3),
+        name: String::new(),
+    };
+    a.shift_loc(&TextEdit::insert(pos(0, 1), "ab"));
+    assert_eq!(a.loc, locof!(0, 0, 0, 5));
+}
+
+#[test]
+fn test_b() {
+    let mut b = B::Call {
+        loc: locof!(0, 0, 2, 3),
+        args: vec![B::C(pos(1, 1), 0), B::D],
+        rest: Some(Box::new(B::A(A {
+            loc: locof!(2, 0, 2, 3),
+            name: String::new(),
+        }))),
+        id: std::any::TypeId::of::<u8>(),
+    };
+    b.shift_loc(&TextEdit::replace(locof!(0, 1, 1, 0), "a"));
+    match b {
+        B::Call {
+            loc, args, rest, ..
+        } => {
+            assert_eq!(loc, locof!(0, 0, 1, 3));
+            match &args[0] {
+                B::C(p, _) => assert_eq!(*p, pos(0, 3)),
+                _ => unreachable!(),
+            }
+            match rest.as_deref() {
+                Some(B::A(a)) => assert_eq!(a.loc, locof!(1, 0, 1, 3)),
+                _ => unreachable!(),
+            }
+        }
+        _ => unreachable!(),
+    }
+}
@@ -0,0 +1,319 @@
+//! Render rustc style diagnostics
+//! # Example
+//! ```
+//! # use srcpos_get::*;
+//! use srcpos_get::diagnostic::Diagnostic;
+//!
+//! let source = "let a = b + 1;\n";
+//! let d = Diagnostic::error("cannot find value `b`", &locof!(0, 8, 0, 9))
+//!     .with_label("not found in this scope")
+//!     .with_secondary(&locof!(0, 4, 0, 5), "while defining this");
+//! assert_eq!(
+//!     d.render_file("main.x", source),
+//!     "\
+//! error: cannot find value `b`
+//!  --> main.x:1:9
+//!   |
+//! 1 | let a = b + 1;
+//!   |         ^ not found in this scope
+//!   |     - while defining this
+//! "
+//! );
+//! ```
+
+use crate::*;
+use std::fmt::{self, Write};
+
+/// Severity of a diagnostic
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Severity {
+    /// help
+    Help,
+    /// note
+    Note,
+    /// warning
+    Warning,
+    /// error
+    Error,
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Severity::Help => "help",
+            Severity::Note => "note",
+            Severity::Warning => "warning",
+            Severity::Error => "error",
+        })
+    }
+}
+
+/// A span with a message
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Label {
+    /// loc
+    pub loc: Loc,
+    /// message, can be empty
+    pub message: String,
+}
+
+impl Label {
+    /// New label
+    pub fn new(at: &impl GetLoc, message: impl Into<String>) -> Self {
+        Self {
+            loc: at.loc(),
+            message: message.into(),
+        }
+    }
+
+    /// New label at a Pos
+    pub fn at_pos(at: &impl GetPos, message: impl Into<String>) -> Self {
+        Self {
+            loc: Loc::new_same_pos(at.pos()),
+            message: message.into(),
+        }
+    }
+}
+
+impl GetLoc for Label {
+    fn loc(&self) -> Loc {
+        self.loc
+    }
+}
+
+/// A diagnostic
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Diagnostic {
+    /// severity
+    pub severity: Severity,
+    /// message
+    pub message: String,
+    /// The primary label
+    pub primary: Label,
+    /// Secondary labels
+    pub secondary: Vec<Label>,
+    /// Notes after the snippet
+    pub notes: Vec<String>,
+}
+
+impl Diagnostic {
+    /// New diagnostic, the primary label has no message
+    pub fn new(severity: Severity, message: impl Into<String>, at: &impl GetLoc) -> Self {
+        Self::with_primary(severity, message, Label::new(at, ""))
+    }
+
+    /// New diagnostic at a Pos, the primary label has no message
+    pub fn at_pos(severity: Severity, message: impl Into<String>, at: &impl GetPos) -> Self {
+        Self::with_primary(severity, message, Label::at_pos(at, ""))
+    }
+
+    fn with_primary(severity: Severity, message: impl Into<String>, primary: Label) -> Self {
+        Self {
+            severity,
+            message: message.into(),
+            primary,
+            secondary: vec![],
+            notes: vec![],
+        }
+    }
+
+    /// New error
+    pub fn error(message: impl Into<String>, at: &impl GetLoc) -> Self {
+        Self::new(Severity::Error, message, at)
+    }
+
+    /// New warning
+    pub fn warning(message: impl Into<String>, at: &impl GetLoc) -> Self {
+        Self::new(Severity::Warning, message, at)
+    }
+
+    /// Set the message of the primary label
+    pub fn with_label(mut self, message: impl Into<String>) -> Self {
+        self.primary.message = message.into();
+        self
+    }
+
+    /// Add a secondary label
+    pub fn with_secondary(mut self, at: &impl GetLoc, message: impl Into<String>) -> Self {
+        self.secondary.push(Label::new(at, message));
+        self
+    }
+
+    /// Add a note
+    pub fn with_note(mut self, note: impl Into<String>) -> Self {
+        self.notes.push(note.into());
+        self
+    }
+
+    /// The message followed by the message of the primary label and the notes,
+    /// for outputs without snippets
+    pub fn full_message(&self) -> String {
+        let mut message = self.message.clone();
+        if !self.primary.message.is_empty() {
+            message = format!("{}\n{}", message, self.primary.message);
+        }
+        for note in self.notes.iter() {
+            message = format!("{}\nnote: {}", message, note);
+        }
+        message
+    }
+
+    /// Render without file name
+    pub fn render(&self, source: &str) -> String {
+        self.render_inner(None, source, &LineIndex::new(source))
+    }
+
+    /// Render with file name
+    pub fn render_file(&self, name: &str, source: &str) -> String {
+        self.render_inner(Some(name), source, &LineIndex::new(source))
+    }
+
+    /// Render with file name and a prebuilt line index of `source`
+    pub fn render_with_index(&self, name: &str, source: &str, index: &LineIndex) -> String {
+        self.render_inner(Some(name), source, index)
+    }
+
+    fn labels(&self) -> impl Iterator<Item = (bool, &Label)> {
+        std::iter::once((true, &self.primary)).chain(self.secondary.iter().map(|l| (false, l)))
+    }
+
+    fn render_inner(&self, name: Option<&str>, source: &str, index: &LineIndex) -> String {
+        let mut lines: Vec<usize> = self
+            .labels()
+            .flat_map(|(_, l)| l.loc.from.line..=last_line(l.loc))
+            .filter(|&line| line < index.line_count())
+            .collect();
+        lines.sort_unstable();
+        lines.dedup();
+        let width = lines.last().map(|l| (l + 1).to_string().len()).unwrap_or(1);
+        let gutter = " ".repeat(width);
+
+        let mut out = String::new();
+        let from = self.primary.loc.from;
+        writeln!(out, "{}: {}", self.severity, self.message).unwrap();
+        match name {
+            Some(name) => writeln!(
+                out,
+                "{}--> {}:{}:{}",
+                gutter,
+                name,
+                from.line + 1,
+                from.column + 1
+            ),
+            None => writeln!(out, "{}--> {}:{}", gutter, from.line + 1, from.column + 1),
+        }
+        .unwrap();
+        writeln!(out, "{} |", gutter).unwrap();
+
+        let mut last = None;
+        for &line in lines.iter() {
+            if let Some(last) = last {
+                if line > last + 1 {
+                    writeln!(out, "...").unwrap();
+                }
+            }
+            last = Some(line);
+
+            let text = &source[index.line_range(line).unwrap()];
+            writeln!(
+                out,
+                "{:>w$} | {}",
+                line + 1,
+                text.replace('\t', "    "),
+                w = width
+            )
+            .unwrap();
+
+            for (primary, label) in self.labels() {
+                let loc = label.loc;
+                let last = last_line(loc);
+                if line < loc.from.line || line > last {
+                    continue;
+                }
+                let start = if line == loc.from.line {
+                    loc.from.column
+                } else {
+                    0
+                };
+                let end = if line == loc.to.line {
+                    loc.to.column
+                } else {
+                    text.chars().count()
+                };
+                let start_col = display_width(text, start);
+                let end_col = display_width(text, end).max(start_col + 1);
+                let mark = if primary { "^" } else { "-" };
+                write!(
+                    out,
+                    "{} | {}{}",
+                    gutter,
+                    " ".repeat(start_col),
+                    mark.repeat(end_col - start_col)
+                )
+                .unwrap();
+                if line == last && !label.message.is_empty() {
+                    write!(out, " {}", label.message).unwrap();
+                }
+                out.push('\n');
+            }
+        }
+
+        if !self.notes.is_empty() {
+            writeln!(out, "{} |", gutter).unwrap();
+            for note in self.notes.iter() {
+                writeln!(out, "{} = note: {}", gutter, note).unwrap();
+            }
+        }
+        out
+    }
+}
+
+impl GetLoc for Diagnostic {
+    fn loc(&self) -> Loc {
+        self.primary.loc
+    }
+}
+
+/// Last line drawn for a loc, a loc ending at the start of a line ends on the line before
+fn last_line(loc: Loc) -> usize {
+    if loc.to.column == 0 && loc.to.line > loc.from.line {
+        loc.to.line - 1
+    } else {
+        loc.to.line.max(loc.from.line)
+    }
+}
+
+/// Display width of the first `column` chars, tabs are 4 wide and wide chars are 2 wide
+fn display_width(text: &str, column: usize) -> usize {
+    let chars = text.chars().take(column);
+    let n = chars.clone().count();
+    let width: usize = chars
+        .map(|c| match c {
+            '\t' => 4,
+            c if is_wide(c) => 2,
+            _ => 1,
+        })
+        .sum();
+    width + (column - n)
+}
+
+/// East Asian Wide and Fullwidth chars, such as CJK and emoji
+fn is_wide(c: char) -> bool {
+    matches!(
+        c as u32,
+        0x1100..=0x115F
+            | 0x2E80..=0x303E
+            | 0x3041..=0x33FF
+            | 0x3400..=0x4DBF
+            | 0x4E00..=0x9FFF
+            | 0xA000..=0xA4CF
+            | 0xAC00..=0xD7A3
+            | 0xF900..=0xFAFF
+            | 0xFE30..=0xFE4F
+            | 0xFF00..=0xFF60
+            | 0xFFE0..=0xFFE6
+            | 0x1F300..=0x1F64F
+            | 0x1F900..=0x1F9FF
+            | 0x20000..=0x3FFFD
+    )
+}
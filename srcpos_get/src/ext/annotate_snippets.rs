@@ -0,0 +1,75 @@
+//! Integration with [`annotate-snippets`](https://docs.rs/annotate-snippets)
+//! # Example
+//! ```
+//! # use srcpos_get::*;
+//! use annotate_snippets::{AnnotationKind, Level, Renderer};
+//! use srcpos_get::ext::annotate_snippets::snippet;
+//!
+//! let source = "let a = b + 1;\n";
+//! let report = &[Level::ERROR.primary_title("cannot find value `b`").element(
+//!     snippet(
+//!         source,
+//!         vec![
+//!             (locof!(0, 8, 0, 9), "not found in this scope", AnnotationKind::Primary),
+//!             (locof!(0, 4, 0, 5), "while defining this", AnnotationKind::Context),
+//!         ],
+//!     )
+//!     .path("main.x"),
+//! )];
+//! assert_eq!(
+//!     Renderer::plain().render(report),
+//!     "\
+//! error: cannot find value `b`
+//!  --> main.x:1:9
+//!   |
+//! 1 | let a = b + 1;
+//!   |     -   ^ not found in this scope
+//!   |     |
+//!   |     while defining this"
+//! );
+//! ```
+
+use crate::*;
+use ::annotate_snippets::{Annotation, AnnotationKind, OptionCow, Snippet};
+
+/// Make an annotation of a node, `index` is the line index of the source
+pub fn annotation<'a, T: GetLoc + ?Sized>(
+    index: &LineIndex,
+    at: &T,
+    label: impl Into<OptionCow<'a>>,
+    kind: AnnotationKind,
+) -> Annotation<'a> {
+    kind.span(index.clamp_range(at.loc())).label(label)
+}
+
+/// Make a snippet of `source` with annotations of nodes
+pub fn snippet<'a, T: GetLoc, L: Into<OptionCow<'a>>>(
+    source: &'a str,
+    annotations: impl IntoIterator<Item = (T, L, AnnotationKind)>,
+) -> Snippet<'a, Annotation<'a>> {
+    snippet_with_index(source, &LineIndex::new(source), annotations)
+}
+
+/// Make a snippet of `source` with annotations of nodes and a prebuilt line index of `source`
+pub fn snippet_with_index<'a, T: GetLoc, L: Into<OptionCow<'a>>>(
+    source: &'a str,
+    index: &LineIndex,
+    annotations: impl IntoIterator<Item = (T, L, AnnotationKind)>,
+) -> Snippet<'a, Annotation<'a>> {
+    Snippet::source(source).annotations(
+        annotations
+            .into_iter()
+            .map(|(at, label, kind)| annotation(index, &at, label, kind)),
+    )
+}
+
+/// Make a snippet of a file in `map` with annotations of nodes, the path is the file name,
+/// `None` if the file is not in `map`
+pub fn file_snippet<'a, T: GetLoc, L: Into<OptionCow<'a>>>(
+    map: &'a SourceMap,
+    file: FileId,
+    annotations: impl IntoIterator<Item = (T, L, AnnotationKind)>,
+) -> Option<Snippet<'a, Annotation<'a>>> {
+    let f = map.get(file)?;
+    Some(snippet_with_index(f.source(), f.line_index(), annotations).path(f.name()))
+}
@@ -0,0 +1,119 @@
+//! Integration with [`ariadne`](https://docs.rs/ariadne)
+//! # Example
+//! ```
+//! # use srcpos_get::*;
+//! use ariadne::{Label, Report, ReportKind};
+//! use srcpos_get::ext::ariadne::{cache, file_span};
+//!
+//! let mut map = SourceMap::new();
+//! let file = map.add("main.x", "let a = b;\n");
+//! let at = FileLoc::new(file, locof!(0, 8, 0, 9));
+//! let span = file_span(&map, &at).unwrap();
+//! let mut out = vec![];
+//! Report::build(ReportKind::Error, span.clone())
+//!     .with_message("cannot find value `b`")
+//!     .with_label(Label::new(span).with_message("not found in this scope"))
+//!     .finish()
+//!     .write(cache(&map), &mut out)
+//!     .unwrap();
+//! ```
+
+use crate::*;
+use ::ariadne::{Cache, Label, Source};
+use std::collections::HashMap;
+use std::fmt;
+use std::ops::Range;
+
+/// Loc in a source, with the char offsets ariadne expects
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct LocSpan<Id> {
+    /// Id of the source
+    pub source: Id,
+    /// loc
+    pub loc: Loc,
+    /// Char offsets of the loc
+    pub range: Range<usize>,
+}
+
+impl<Id> LocSpan<Id> {
+    /// New span, `index` is the line index of the source
+    pub fn new<T: GetLoc + ?Sized>(source: Id, index: &LineIndex, at: &T) -> Self {
+        let loc = at.loc();
+        let range = index.clamp_range(loc);
+        Self {
+            source,
+            loc,
+            range: index.char_offset(range.start)..index.char_offset(range.end),
+        }
+    }
+}
+
+impl<Id: PartialEq + ToOwned> ::ariadne::Span for LocSpan<Id> {
+    type SourceId = Id;
+
+    fn source(&self) -> &Id {
+        &self.source
+    }
+
+    fn start(&self) -> usize {
+        self.range.start
+    }
+
+    fn end(&self) -> usize {
+        self.range.end
+    }
+}
+
+impl<Id> GetLoc for LocSpan<Id> {
+    fn loc(&self) -> Loc {
+        self.loc
+    }
+}
+
+/// Make a label, `index` is the line index of the source
+pub fn label<Id: PartialEq + ToOwned, T: GetLoc + ?Sized>(
+    source: Id,
+    index: &LineIndex,
+    at: &T,
+) -> Label<LocSpan<Id>> {
+    Label::new(LocSpan::new(source, index, at))
+}
+
+/// Make a span of a node knowing its file, `None` if the file is not in `map`
+pub fn file_span<T: GetFileLoc + ?Sized>(map: &SourceMap, at: &T) -> Option<LocSpan<FileId>> {
+    let at = at.file_loc();
+    Some(LocSpan::new(at.file, map.line_index(at.file)?, &at))
+}
+
+/// Make a [`Cache`] of a [`SourceMap`], displaying files by name
+pub fn cache(map: &SourceMap) -> SourceMapCache<'_> {
+    SourceMapCache {
+        map,
+        sources: HashMap::new(),
+    }
+}
+
+/// A [`Cache`] of a [`SourceMap`], made by [`cache`]
+pub struct SourceMapCache<'a> {
+    map: &'a SourceMap,
+    sources: HashMap<FileId, Source<&'a str>>,
+}
+
+impl<'a> Cache<FileId> for SourceMapCache<'a> {
+    type Storage = &'a str;
+
+    fn fetch(&mut self, id: &FileId) -> Result<&Source<&'a str>, impl fmt::Debug> {
+        if !self.sources.contains_key(id) {
+            let source = self
+                .map
+                .source(*id)
+                .ok_or_else(|| format!("Failed to fetch source '{}'", id))?;
+            self.sources.insert(*id, Source::from(source));
+        }
+        Ok::<_, String>(&self.sources[id])
+    }
+
+    fn display<'b>(&self, id: &'b FileId) -> Option<impl fmt::Display + 'b> {
+        self.map.name(*id).map(|name| name.to_string())
+    }
+}
@@ -0,0 +1,58 @@
+//! Integration with [`chumsky`](https://docs.rs/chumsky)
+//!
+//! [`FileLoc`] is a chumsky span whose context is the [`FileId`],
+//! use [`map_span`] to get it from a text input
+//! # Example
+//! ```
+//! # use srcpos_get::*;
+//! use chumsky::prelude::*;
+//! use srcpos_get::ext::chumsky::map_span;
+//!
+//! #[derive(GetLoc, GetFileLoc)]
+//! struct Ident {
+//!     name: String,
+//!     #[loc]
+//!     at: FileLoc,
+//! }
+//!
+//! let mut map = SourceMap::new();
+//! let file = map.add("main.x", "a\n  bc");
+//! let input = map.source(file).unwrap().map_span(map_span(file, map.line_index(file).unwrap()));
+//! let ident = text::ascii::ident::<_, extra::Default>()
+//!     .map_with(|name: &str, e| Ident { name: name.to_string(), at: e.span() });
+//! let idents = ident.padded().repeated().collect::<Vec<_>>().parse(input).unwrap();
+//! assert_eq!(idents[1].loc(), locof!(1, 2, 1, 4));
+//! assert_eq!(idents[1].file_loc().file, file);
+//! ```
+
+use crate::*;
+use ::chumsky::span::{SimpleSpan, Span};
+use std::ops::Range;
+
+impl Span for FileLoc {
+    type Context = FileId;
+    type Offset = Pos;
+
+    fn new(context: FileId, range: Range<Pos>) -> Self {
+        FileLoc::new(context, Loc::new(range.start, range.end))
+    }
+
+    fn context(&self) -> FileId {
+        self.file
+    }
+
+    fn start(&self) -> Pos {
+        self.loc.from
+    }
+
+    fn end(&self) -> Pos {
+        self.loc.to
+    }
+}
+
+/// Make a function converting the byte spans of a text input to FileLoc, for `Input::map_span`
+///
+/// `index` is the line index of the text
+pub fn map_span(file: FileId, index: &LineIndex) -> impl Fn(SimpleSpan) -> FileLoc + '_ {
+    move |span| FileLoc::new(file, index.loc(span.start..span.end))
+}
@@ -0,0 +1,110 @@
+//! Integration with [`codespan-reporting`](https://docs.rs/codespan-reporting)
+//! # Example
+//! ```
+//! # use srcpos_get::*;
+//! use codespan_reporting::diagnostic::Severity;
+//! use srcpos_get::ext::codespan::{diagnostic, secondary_label};
+//!
+//! #[derive(GetLoc)]
+//! struct Ident {
+//!     loc: Loc,
+//! }
+//!
+//! let mut map = SourceMap::new();
+//! let file = map.add("main.x", "let a = b;");
+//! let index = map.line_index(file).unwrap();
+//! let node = Ident { loc: locof!(0, 8, 0, 9) };
+//! let d = diagnostic(Severity::Error, file, index, &node, "cannot find value `b`")
+//!     .with_labels(vec![secondary_label(file, index, &locof!(0, 4, 0, 5))]);
+//! assert_eq!(d.labels[0].range, 8..9);
+//! assert_eq!(d.labels[1].range, 4..5);
+//! ```
+
+use crate::*;
+use codespan_reporting::diagnostic::{Diagnostic, Label, LabelStyle, Severity};
+use codespan_reporting::files::{Error, Files};
+use std::fmt::Display;
+use std::ops::Range;
+
+/// Make a label, `index` is the line index of the file
+pub fn label<F, T: GetLoc + ?Sized>(
+    style: LabelStyle,
+    file_id: F,
+    index: &LineIndex,
+    at: &T,
+) -> Label<F> {
+    Label::new(style, file_id, index.clamp_range(at.loc()))
+}
+
+/// Make a primary label, `index` is the line index of the file
+pub fn primary_label<F, T: GetLoc + ?Sized>(file_id: F, index: &LineIndex, at: &T) -> Label<F> {
+    label(LabelStyle::Primary, file_id, index, at)
+}
+
+/// Make a secondary label, `index` is the line index of the file
+pub fn secondary_label<F, T: GetLoc + ?Sized>(file_id: F, index: &LineIndex, at: &T) -> Label<F> {
+    label(LabelStyle::Secondary, file_id, index, at)
+}
+
+/// Make a diagnostic with a primary label at `at`, `index` is the line index of the file
+pub fn diagnostic<F, T: GetLoc + ?Sized>(
+    severity: Severity,
+    file_id: F,
+    index: &LineIndex,
+    at: &T,
+    message: impl Display,
+) -> Diagnostic<F> {
+    Diagnostic::new(severity)
+        .with_message(message)
+        .with_labels(vec![primary_label(file_id, index, at)])
+}
+
+/// Make a diagnostic with a primary label at a node knowing its file,
+/// `None` if the file is not in `map`
+pub fn file_diagnostic<T: GetFileLoc + ?Sized>(
+    severity: Severity,
+    map: &SourceMap,
+    at: &T,
+    message: impl Display,
+) -> Option<Diagnostic<FileId>> {
+    let at = at.file_loc();
+    let index = map.line_index(at.file)?;
+    Some(diagnostic(severity, at.file, index, &at, message))
+}
+
+impl<'a> Files<'a> for SourceMap {
+    type FileId = FileId;
+    type Name = &'a str;
+    type Source = &'a str;
+
+    fn name(&'a self, id: FileId) -> Result<&'a str, Error> {
+        self.name(id).ok_or(Error::FileMissing)
+    }
+
+    fn source(&'a self, id: FileId) -> Result<&'a str, Error> {
+        self.source(id).ok_or(Error::FileMissing)
+    }
+
+    fn line_index(&'a self, id: FileId, byte_index: usize) -> Result<usize, Error> {
+        let index = SourceMap::line_index(self, id).ok_or(Error::FileMissing)?;
+        Ok(index.line(byte_index))
+    }
+
+    fn column_number(&'a self, id: FileId, _: usize, byte_index: usize) -> Result<usize, Error> {
+        let index = SourceMap::line_index(self, id).ok_or(Error::FileMissing)?;
+        Ok(index.pos(byte_index).column + 1)
+    }
+
+    fn line_range(&'a self, id: FileId, line_index: usize) -> Result<Range<usize>, Error> {
+        let index = SourceMap::line_index(self, id).ok_or(Error::FileMissing)?;
+        let range = index.line_range(line_index).ok_or(Error::LineTooLarge {
+            given: line_index,
+            max: index.line_count() - 1,
+        })?;
+        // include the `\n`
+        match index.line_range(line_index + 1) {
+            Some(next) => Ok(range.start..next.start),
+            None => Ok(range),
+        }
+    }
+}
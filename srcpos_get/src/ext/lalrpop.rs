@@ -0,0 +1,82 @@
+//! Lexer adapter for [`lalrpop`](https://docs.rs/lalrpop) grammars whose location type is [`Pos`]
+//!
+//! Declare the location type in the grammar and build Loc from `@L` and `@R`
+//! ```text
+//! extern {
+//!     type Location = Pos;
+//!     type Error = LexError;
+//!     enum Tok { "ident" => Tok::Ident(<String>), .. }
+//! }
+//!
+//! Ident: Ident = <l:@L> <name:"ident"> <r:@R> => Ident { name, loc: Loc::new(l, r) };
+//! ```
+//! then pass `PosLexer::new(source, lexer)` to the parser
+//! # Example
+//! ```
+//! # use srcpos_get::*;
+//! use srcpos_get::ext::lalrpop::PosLexer;
+//!
+//! let source = "a\n  bc";
+//! let tokens = vec![Ok::<_, ()>((0, "a", 1)), Ok((4, "bc", 6))];
+//! let tokens: Vec<_> = PosLexer::new(source, tokens).collect();
+//! assert_eq!(tokens[1], Ok((pos(1, 2), "bc", pos(1, 4))));
+//! ```
+
+use crate::shift::advance;
+use crate::*;
+
+/// A token of a lalrpop lexer, with start and end locations
+pub type Spanned<Tok, L, E> = Result<(L, Tok, L), E>;
+
+/// Convert the byte offsets of a lalrpop lexer to Pos
+///
+/// Pos are counted from the previous offset, so it is fast when the offsets do not decrease
+pub struct PosLexer<'a, I> {
+    source: &'a str,
+    tokens: I,
+    offset: usize,
+    pos: Pos,
+}
+
+impl<'a, I> PosLexer<'a, I> {
+    /// Wrap the tokens of a lexer, `source` is the text it lexes
+    pub fn new<Tok, E, T>(source: &'a str, tokens: T) -> Self
+    where
+        T: IntoIterator<IntoIter = I>,
+        I: Iterator<Item = Spanned<Tok, usize, E>>,
+    {
+        Self {
+            source,
+            tokens: tokens.into_iter(),
+            offset: 0,
+            pos: Pos::default(),
+        }
+    }
+
+    /// Pos of a byte offset, offsets after the end are clamped to the end,
+    /// offsets inside a char are at that char
+    pub fn pos(&mut self, offset: usize) -> Pos {
+        let mut offset = offset.min(self.source.len());
+        while !self.source.is_char_boundary(offset) {
+            offset -= 1;
+        }
+        if offset < self.offset {
+            self.offset = 0;
+            self.pos = Pos::default();
+        }
+        self.pos = advance(self.pos, &self.source[self.offset..offset]);
+        self.offset = offset;
+        self.pos
+    }
+}
+
+impl<Tok, E, I: Iterator<Item = Spanned<Tok, usize, E>>> Iterator for PosLexer<'_, I> {
+    type Item = Spanned<Tok, Pos, E>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        Some(self.tokens.next()?.map(|(l, tok, r)| {
+            let l = self.pos(l);
+            (l, tok, self.pos(r))
+        }))
+    }
+}
@@ -0,0 +1,131 @@
+//! Integration with [`logos`](https://docs.rs/logos)
+//! # Example
+//! ```
+//! # use srcpos_get::*;
+//! use logos::Logos;
+//! use srcpos_get::ext::logos::{LocLexer, Token};
+//!
+//! #[derive(Logos, Debug, PartialEq)]
+//! #[logos(skip r"[ \n]+")]
+//! enum Tok {
+//!     #[regex("[a-z]+")]
+//!     Ident,
+//!     #[token("=")]
+//!     Eq,
+//! }
+//!
+//! let tokens: Vec<_> = LocLexer::new(Tok::lexer("a =\n  bc")).collect();
+//! assert_eq!(tokens[2], Ok(Token::new(Tok::Ident, locof!(1, 2, 1, 4))));
+//! ```
+
+use crate::shift::advance;
+use crate::*;
+use ::logos::{Lexer, Logos};
+use std::ops::{Deref, DerefMut};
+
+/// A token with Loc
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Token<T> {
+    /// kind
+    pub kind: T,
+    /// loc
+    pub loc: Loc,
+}
+
+impl<T> Token<T> {
+    /// New token
+    #[inline]
+    pub const fn new(kind: T, loc: Loc) -> Self {
+        Self { kind, loc }
+    }
+}
+
+impl<T> GetLoc for Token<T> {
+    fn loc(&self) -> Loc {
+        self.loc
+    }
+}
+
+impl<T> GetPos for Token<T> {
+    fn pos(&self) -> Pos {
+        self.loc.from
+    }
+}
+
+impl<T> GetLocMut for Token<T> {
+    fn loc_mut(&mut self) -> &mut Loc {
+        &mut self.loc
+    }
+}
+
+impl<T> SetLoc for Token<T> {
+    fn set_loc(&mut self, loc: Loc) {
+        self.loc = loc
+    }
+}
+
+/// Iterate tokens of a [`Lexer`] with Loc, errors are also tokens
+///
+/// Pos are counted from the end of the previous token, the source is not rescanned
+pub struct LocLexer<'s, T: Logos<'s, Source = str>> {
+    lexer: Lexer<'s, T>,
+    offset: usize,
+    pos: Pos,
+}
+
+impl<'s, T: Logos<'s, Source = str>> LocLexer<'s, T> {
+    /// Wrap a lexer
+    pub fn new(lexer: Lexer<'s, T>) -> Self {
+        Self {
+            lexer,
+            offset: 0,
+            pos: Pos::default(),
+        }
+    }
+
+    /// Unwrap the lexer
+    pub fn into_inner(self) -> Lexer<'s, T> {
+        self.lexer
+    }
+
+    /// Pos of a byte offset, must not be before the last one
+    fn advance(&mut self, offset: usize) -> Pos {
+        self.pos = advance(self.pos, &self.lexer.source()[self.offset..offset]);
+        self.offset = offset;
+        self.pos
+    }
+}
+
+impl<'s, T: Logos<'s, Source = str>> From<Lexer<'s, T>> for LocLexer<'s, T> {
+    fn from(lexer: Lexer<'s, T>) -> Self {
+        Self::new(lexer)
+    }
+}
+
+impl<'s, T: Logos<'s, Source = str>> Iterator for LocLexer<'s, T> {
+    type Item = Result<Token<T>, Token<T::Error>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let kind = self.lexer.next()?;
+        let span = self.lexer.span();
+        let loc = Loc::new(self.advance(span.start), self.advance(span.end));
+        Some(match kind {
+            Ok(kind) => Ok(Token::new(kind, loc)),
+            Err(error) => Err(Token::new(error, loc)),
+        })
+    }
+}
+
+impl<'s, T: Logos<'s, Source = str>> Deref for LocLexer<'s, T> {
+    type Target = Lexer<'s, T>;
+
+    fn deref(&self) -> &Lexer<'s, T> {
+        &self.lexer
+    }
+}
+
+impl<'s, T: Logos<'s, Source = str>> DerefMut for LocLexer<'s, T> {
+    fn deref_mut(&mut self) -> &mut Lexer<'s, T> {
+        &mut self.lexer
+    }
+}
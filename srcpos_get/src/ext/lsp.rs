@@ -0,0 +1,133 @@
+//! Integration with [`lsp-types`](https://docs.rs/lsp-types)
+//!
+//! Columns of LSP positions are counted in the negotiated [`PositionEncodingKind`],
+//! positions after the end of a line or the text are clamped to the end
+//! # Example
+//! ```
+//! # use srcpos_get::*;
+//! use lsp_types::{Position, PositionEncodingKind, Range};
+//! use srcpos_get::ext::lsp::{encoding, range, to_loc};
+//!
+//! let index = LineIndex::new("let a = \"😀\";\nb");
+//! let enc = encoding(&PositionEncodingKind::UTF16);
+//! let r = range(&index, &locof!(0, 9, 0, 10), enc);
+//! assert_eq!(r, Range::new(Position::new(0, 9), Position::new(0, 11)));
+//! assert_eq!(to_loc(&index, r, enc), locof!(0, 9, 0, 10));
+//! ```
+
+use crate::diagnostic::{self, Severity};
+use crate::*;
+use ::lsp_types::{
+    Diagnostic, DiagnosticRelatedInformation, DiagnosticSeverity, Location, Position,
+    PositionEncodingKind, Range, Uri,
+};
+
+/// Column encoding of a position encoding kind, unknown kinds are UTF-16, the default of LSP
+pub fn encoding(kind: &PositionEncodingKind) -> ColumnEncoding {
+    if *kind == PositionEncodingKind::UTF8 {
+        ColumnEncoding::Utf8
+    } else if *kind == PositionEncodingKind::UTF32 {
+        ColumnEncoding::Utf32
+    } else {
+        ColumnEncoding::Utf16
+    }
+}
+
+/// Position encoding kind of a column encoding
+pub fn encoding_kind(encoding: ColumnEncoding) -> PositionEncodingKind {
+    match encoding {
+        ColumnEncoding::Utf8 => PositionEncodingKind::UTF8,
+        ColumnEncoding::Utf16 => PositionEncodingKind::UTF16,
+        ColumnEncoding::Utf32 => PositionEncodingKind::UTF32,
+    }
+}
+
+/// Convert a Pos to Position, `index` is the line index of the text
+pub fn position(index: &LineIndex, pos: Pos, encoding: ColumnEncoding) -> Position {
+    let pos = index.pos(index.clamp_offset(pos));
+    let pos = index.to_encoding(pos, encoding).unwrap_or(pos);
+    Position::new(pos.line as u32, pos.column as u32)
+}
+
+/// Convert a Position to Pos, `index` is the line index of the text
+pub fn to_pos(index: &LineIndex, position: Position, encoding: ColumnEncoding) -> Pos {
+    let pos = Pos::new(position.line as usize, position.character as usize);
+    match index.from_encoding(pos, encoding) {
+        Some(pos) => pos,
+        None => index.pos(index.line_range(pos.line).map_or(index.len(), |r| r.end)),
+    }
+}
+
+/// Convert the Loc of a node to Range, `index` is the line index of the text
+pub fn range<T: GetLoc + ?Sized>(index: &LineIndex, at: &T, encoding: ColumnEncoding) -> Range {
+    let loc = at.loc();
+    Range::new(
+        position(index, loc.from, encoding),
+        position(index, loc.to, encoding),
+    )
+}
+
+/// Convert a Range to Loc, `index` is the line index of the text
+pub fn to_loc(index: &LineIndex, range: Range, encoding: ColumnEncoding) -> Loc {
+    Loc::new(
+        to_pos(index, range.start, encoding),
+        to_pos(index, range.end, encoding),
+    )
+}
+
+/// Make the Location of a node in the file at `uri`, `index` is the line index of the file
+pub fn location<T: GetLoc + ?Sized>(
+    uri: Uri,
+    index: &LineIndex,
+    at: &T,
+    encoding: ColumnEncoding,
+) -> Location {
+    Location::new(uri, range(index, at, encoding))
+}
+
+/// Severity of a diagnostic
+pub fn severity(severity: Severity) -> DiagnosticSeverity {
+    match severity {
+        Severity::Help => DiagnosticSeverity::HINT,
+        Severity::Note => DiagnosticSeverity::INFORMATION,
+        Severity::Warning => DiagnosticSeverity::WARNING,
+        Severity::Error => DiagnosticSeverity::ERROR,
+    }
+}
+
+/// Make a diagnostic at a node, `index` is the line index of the file
+pub fn diagnostic<T: GetLoc + ?Sized>(
+    severity: Severity,
+    index: &LineIndex,
+    at: &T,
+    encoding: ColumnEncoding,
+    message: impl Into<String>,
+) -> Diagnostic {
+    let mut d = Diagnostic::new_simple(range(index, at, encoding), message.into());
+    d.severity = Some(self::severity(severity));
+    d
+}
+
+/// Convert a [`diagnostic::Diagnostic`] in the file at `uri`, `index` is the line index of the file
+///
+/// Secondary labels become related information, the message is [`diagnostic::Diagnostic::full_message`]
+pub fn from_diagnostic(
+    uri: &Uri,
+    index: &LineIndex,
+    d: &diagnostic::Diagnostic,
+    encoding: ColumnEncoding,
+) -> Diagnostic {
+    let mut r = diagnostic(d.severity, index, &d.primary, encoding, d.full_message());
+    if !d.secondary.is_empty() {
+        r.related_information = Some(
+            d.secondary
+                .iter()
+                .map(|l| DiagnosticRelatedInformation {
+                    location: location(uri.clone(), index, l, encoding),
+                    message: l.message.clone(),
+                })
+                .collect(),
+        );
+    }
+    r
+}
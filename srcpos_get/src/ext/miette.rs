@@ -0,0 +1,123 @@
+//! Integration with [`miette`](https://docs.rs/miette)
+//! # Example
+//! ```
+//! # use srcpos_get::*;
+//! use srcpos_get::ext::miette::LocError;
+//! use std::fmt;
+//!
+//! #[derive(Debug, GetLoc)]
+//! struct UnknownName {
+//!     name: String,
+//!     loc: Loc,
+//! }
+//!
+//! impl fmt::Display for UnknownName {
+//!     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+//!         write!(f, "cannot find value `{}`", self.name)
+//!     }
+//! }
+//!
+//! impl std::error::Error for UnknownName {}
+//!
+//! let mut map = SourceMap::new();
+//! let file = map.add("main.x", "let a = b;");
+//! let err = UnknownName { name: "b".to_string(), loc: locof!(0, 8, 0, 9) };
+//! let report = miette::Report::new(LocError::from_file(err, map.get(file).unwrap()).with_label("not found"));
+//! # let _ = report;
+//! ```
+
+use crate::*;
+use ::miette::{Diagnostic, LabeledSpan, NamedSource, SourceCode, SourceSpan};
+use std::error::Error;
+use std::fmt;
+
+/// Convert a Loc to SourceSpan, `index` is the line index of the source
+pub fn source_span<T: GetLoc + ?Sized>(index: &LineIndex, at: &T) -> SourceSpan {
+    index.clamp_range(at.loc()).into()
+}
+
+/// Wrap an error that has Loc into a [`Diagnostic`] labeling its Loc
+#[derive(Debug)]
+pub struct LocError<E> {
+    error: E,
+    span: SourceSpan,
+    label: Option<String>,
+    source_code: Option<NamedSource<String>>,
+}
+
+impl<E: GetLoc> LocError<E> {
+    /// Wrap an error, `index` is the line index of the source
+    pub fn new(error: E, index: &LineIndex) -> Self {
+        let span = source_span(index, &error);
+        Self {
+            error,
+            span,
+            label: None,
+            source_code: None,
+        }
+    }
+
+    /// Wrap an error in a file, the file contents is attached as source code
+    pub fn from_file(error: E, file: &SourceFile) -> Self {
+        Self::new(error, file.line_index()).with_source_code(file.name(), file.source())
+    }
+}
+
+impl<E> LocError<E> {
+    /// Set the text of the label
+    pub fn with_label(mut self, label: impl Into<String>) -> Self {
+        self.label = Some(label.into());
+        self
+    }
+
+    /// Attach source code
+    pub fn with_source_code(mut self, name: impl AsRef<str>, source: impl Into<String>) -> Self {
+        self.source_code = Some(NamedSource::new(name, source.into()));
+        self
+    }
+
+    /// The wrapped error
+    pub fn error(&self) -> &E {
+        &self.error
+    }
+
+    /// Unwrap the error
+    pub fn into_inner(self) -> E {
+        self.error
+    }
+
+    /// The span of the label
+    pub fn span(&self) -> SourceSpan {
+        self.span
+    }
+}
+
+impl<E: GetLoc> GetLoc for LocError<E> {
+    fn loc(&self) -> Loc {
+        self.error.loc()
+    }
+}
+
+impl<E: fmt::Display> fmt::Display for LocError<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.error.fmt(f)
+    }
+}
+
+impl<E: Error> Error for LocError<E> {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        self.error.source()
+    }
+}
+
+impl<E: Error> Diagnostic for LocError<E> {
+    fn source_code(&self) -> Option<&dyn SourceCode> {
+        self.source_code.as_ref().map(|s| s as &dyn SourceCode)
+    }
+
+    fn labels(&self) -> Option<Box<dyn Iterator<Item = LabeledSpan> + '_>> {
+        Some(Box::new(std::iter::once(
+            LabeledSpan::new_primary_with_span(self.label.clone(), self.span),
+        )))
+    }
+}
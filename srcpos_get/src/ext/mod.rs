@@ -0,0 +1,32 @@
+//! Integrations with other crates, each behind the cargo feature of the same name
+
+#[cfg(feature = "annotate-snippets")]
+pub mod annotate_snippets;
+#[cfg(feature = "ariadne")]
+pub mod ariadne;
+#[cfg(feature = "chumsky")]
+pub mod chumsky;
+#[cfg(feature = "codespan")]
+pub mod codespan;
+#[cfg(feature = "lalrpop")]
+pub mod lalrpop;
+#[cfg(feature = "logos")]
+pub mod logos;
+#[cfg(feature = "lsp")]
+pub mod lsp;
+#[cfg(feature = "miette")]
+pub mod miette;
+#[cfg(feature = "nom")]
+pub mod nom;
+#[cfg(feature = "pest")]
+pub mod pest;
+#[cfg(feature = "proc-macro2")]
+pub mod proc_macro2;
+#[cfg(feature = "rowan")]
+pub mod rowan;
+#[cfg(feature = "sarif")]
+pub mod sarif;
+#[cfg(feature = "serde")]
+pub mod serde;
+#[cfg(feature = "tree-sitter")]
+pub mod tree_sitter;
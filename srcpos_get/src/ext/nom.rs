@@ -0,0 +1,63 @@
+//! Integration with [`nom`](https://docs.rs/nom) and [`nom_locate`](https://docs.rs/nom_locate)
+//!
+//! Lines and columns are 1-based in nom_locate and 0-based in Pos, columns count chars
+//! # Example
+//! ```
+//! # use srcpos_get::*;
+//! use nom::bytes::complete::tag;
+//! use nom::character::complete::{alpha1, multispace0};
+//! use nom::sequence::preceded;
+//! use nom::{IResult, Parser};
+//! use nom_locate::LocatedSpan;
+//! use srcpos_get::ext::nom::located;
+//!
+//! type Span<'a> = LocatedSpan<&'a str>;
+//!
+//! #[derive(GetLoc)]
+//! struct Ident {
+//!     name: String,
+//!     loc: Loc,
+//! }
+//!
+//! fn ident(input: Span) -> IResult<Span, Ident> {
+//!     located(alpha1)
+//!         .map(|(loc, name): (Loc, Span)| Ident { name: name.to_string(), loc })
+//!         .parse(input)
+//! }
+//!
+//! let (_, a) = preceded((tag("变"), multispace0), ident).parse(Span::new("变\n  ab ")).unwrap();
+//! assert_eq!(a.loc(), locof!(1, 2, 1, 4));
+//! ```
+
+use crate::shift::advance;
+use crate::*;
+use ::nom::{AsBytes, IResult, Parser};
+use ::nom_locate::LocatedSpan;
+
+impl<T: AsBytes, X> GetPos for LocatedSpan<T, X> {
+    fn pos(&self) -> Pos {
+        Pos::new(
+            (self.location_line() as usize).saturating_sub(1),
+            self.get_utf8_column().saturating_sub(1),
+        )
+    }
+}
+
+impl<X> GetLoc for LocatedSpan<&str, X> {
+    fn loc(&self) -> Loc {
+        let from = self.pos();
+        Loc::new(from, advance(from, self.fragment()))
+    }
+}
+
+/// Wrap a parser to also return the Loc from the start of its input to the start of the rest
+pub fn located<T: AsBytes, X, P: Parser<LocatedSpan<T, X>>>(
+    mut parser: P,
+) -> impl Parser<LocatedSpan<T, X>, Output = (Loc, P::Output), Error = P::Error> {
+    move |input: LocatedSpan<T, X>| -> IResult<_, _, P::Error> {
+        let from = input.pos();
+        let (rest, output) = parser.parse(input)?;
+        let loc = Loc::new(from, rest.pos());
+        Ok((rest, (loc, output)))
+    }
+}
@@ -0,0 +1,75 @@
+//! Integration with [`pest`](https://docs.rs/pest)
+//!
+//! Lines and columns are 1-based in pest and 0-based in Loc.
+//! The start of a [`Pair`] uses the line index of its pairs, the start of a [`Span`] or [`Position`]
+//! is counted from the start of the input, for many spans of a large input prefer
+//! `LineIndex::loc(span.start()..span.end())`.
+//! [`Position`] has an inherent `pos` returning its byte offset, call `GetPos::pos(&position)` instead
+//! # Example
+//! ```
+//! # use srcpos_get::*;
+//! use pest::Parser;
+//! use pest_derive::Parser;
+//!
+//! #[derive(Parser)]
+//! #[grammar_inline = "ident = { ASCII_ALPHA+ } \n main = { SOI ~ (ident ~ NEWLINE?)* ~ EOI }"]
+//! struct Grammar;
+//!
+//! #[derive(GetLoc)]
+//! struct Ident {
+//!     name: String,
+//!     loc: Loc,
+//! }
+//!
+//! # fn main() {
+//! let main = Grammar::parse(Rule::main, "a\nbc").unwrap().next().unwrap();
+//! let idents: Vec<Ident> = main
+//!     .into_inner()
+//!     .filter(|pair| pair.as_rule() == Rule::ident)
+//!     .map(|pair| Ident { name: pair.as_str().to_string(), loc: pair.loc() })
+//!     .collect();
+//! assert_eq!(idents[1].loc(), locof!(1, 0, 1, 2));
+//! # }
+//! ```
+
+use crate::shift::advance;
+use crate::*;
+use ::pest::iterators::Pair;
+use ::pest::{Position, RuleType, Span};
+
+/// Convert a pest line and column
+fn line_col((line, col): (usize, usize)) -> Pos {
+    Pos::new(line.saturating_sub(1), col.saturating_sub(1))
+}
+
+impl GetPos for Position<'_> {
+    fn pos(&self) -> Pos {
+        line_col(self.line_col())
+    }
+}
+
+impl GetPos for Span<'_> {
+    fn pos(&self) -> Pos {
+        GetPos::pos(&self.start_pos())
+    }
+}
+
+impl GetLoc for Span<'_> {
+    fn loc(&self) -> Loc {
+        let from = self.pos();
+        Loc::new(from, advance(from, self.as_str()))
+    }
+}
+
+impl<R: RuleType> GetPos for Pair<'_, R> {
+    fn pos(&self) -> Pos {
+        line_col(self.line_col())
+    }
+}
+
+impl<R: RuleType> GetLoc for Pair<'_, R> {
+    fn loc(&self) -> Loc {
+        let from = self.pos();
+        Loc::new(from, advance(from, self.as_str()))
+    }
+}
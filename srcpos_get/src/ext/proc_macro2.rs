@@ -0,0 +1,123 @@
+//! Integration with [`proc-macro2`](https://docs.rs/proc-macro2)
+//!
+//! Spans are converted with the `span-locations` feature of proc-macro2,
+//! lines are 1-based there and 0-based in Loc
+//! # Example
+//! ```
+//! # use srcpos_get::*;
+//! use proc_macro2::{Ident, TokenStream, TokenTree};
+//! use srcpos_get::ext::proc_macro2::GetSpan;
+//!
+//! #[derive(GetLoc, GetSpan)]
+//! struct Name {
+//!     #[loc]
+//!     #[span]
+//!     ident: Ident,
+//! }
+//!
+//! let tokens: TokenStream = "a\n  b".parse().unwrap();
+//! let names: Vec<Name> = tokens
+//!     .into_iter()
+//!     .filter_map(|t| match t {
+//!         TokenTree::Ident(ident) => Some(Name { ident }),
+//!         _ => None,
+//!     })
+//!     .collect();
+//! assert_eq!(names[1].loc(), locof!(1, 2, 1, 3));
+//! assert_eq!(names[1].span().start().line, 2);
+//! ```
+
+use crate::*;
+use ::proc_macro2::{Group, Ident, LineColumn, Literal, Punct, TokenTree};
+use std::rc::Rc;
+use std::sync::Arc;
+
+pub use ::proc_macro2::Span;
+#[cfg(feature = "srcpos_get_derive")]
+pub use srcpos_get_derive::GetSpan;
+
+/// Calculate Span, for reporting errors of macro input like `syn::Error::new(node.span(), ..)`
+pub trait GetSpan {
+    /// Calculate Span
+    fn span(&self) -> Span;
+}
+
+impl GetSpan for Span {
+    fn span(&self) -> Span {
+        *self
+    }
+}
+
+macro_rules! impl_token {
+    ($($t:ty),*) => {
+        $(
+            impl GetSpan for $t {
+                fn span(&self) -> Span {
+                    <$t>::span(self)
+                }
+            }
+
+            impl GetLoc for $t {
+                fn loc(&self) -> Loc {
+                    <$t>::span(self).loc()
+                }
+            }
+
+            impl GetPos for $t {
+                fn pos(&self) -> Pos {
+                    <$t>::span(self).pos()
+                }
+            }
+        )*
+    };
+}
+
+impl_token!(Ident, Literal, Punct, Group, TokenTree);
+
+impl<T: GetSpan + ?Sized> GetSpan for &T {
+    fn span(&self) -> Span {
+        (**self).span()
+    }
+}
+
+impl<T: GetSpan + ?Sized> GetSpan for &mut T {
+    fn span(&self) -> Span {
+        (**self).span()
+    }
+}
+
+impl<T: GetSpan + ?Sized> GetSpan for Box<T> {
+    fn span(&self) -> Span {
+        (**self).span()
+    }
+}
+
+impl<T: GetSpan + ?Sized> GetSpan for Rc<T> {
+    fn span(&self) -> Span {
+        (**self).span()
+    }
+}
+
+impl<T: GetSpan + ?Sized> GetSpan for Arc<T> {
+    fn span(&self) -> Span {
+        (**self).span()
+    }
+}
+
+impl GetPos for LineColumn {
+    fn pos(&self) -> Pos {
+        Pos::new(self.line.saturating_sub(1), self.column)
+    }
+}
+
+impl GetLoc for Span {
+    fn loc(&self) -> Loc {
+        Loc::new(self.start().pos(), self.end().pos())
+    }
+}
+
+impl GetPos for Span {
+    fn pos(&self) -> Pos {
+        self.start().pos()
+    }
+}
@@ -0,0 +1,70 @@
+//! Integration with [`rowan`](https://docs.rs/rowan)
+//!
+//! Get Loc of nodes, tokens and ranges through [`Indexed`] with the line index of the root text
+//! # Example
+//! ```
+//! # use srcpos_get::*;
+//! use rowan::{GreenNodeBuilder, Language, SyntaxKind};
+//!
+//! #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+//! enum Lang {}
+//!
+//! impl Language for Lang {
+//!     type Kind = u16;
+//!     fn kind_from_raw(raw: SyntaxKind) -> u16 {
+//!         raw.0
+//!     }
+//!     fn kind_to_raw(kind: u16) -> SyntaxKind {
+//!         SyntaxKind(kind)
+//!     }
+//! }
+//!
+//! #[derive(GetByteRange)]
+//! struct Item {
+//!     #[loc]
+//!     syntax: rowan::SyntaxNode<Lang>,
+//! }
+//!
+//! let mut builder = GreenNodeBuilder::new();
+//! builder.start_node(SyntaxKind(0));
+//! builder.token(SyntaxKind(1), "a\n");
+//! builder.start_node(SyntaxKind(2));
+//! builder.token(SyntaxKind(1), "  变量");
+//! builder.finish_node();
+//! builder.finish_node();
+//! let root = rowan::SyntaxNode::<Lang>::new_root(builder.finish());
+//! let index = LineIndex::new(&root.text().to_string());
+//! let item = Item { syntax: root.last_child().unwrap() };
+//! assert_eq!(Indexed::new(&index, &item).loc(), locof!(1, 0, 1, 4));
+//! ```
+
+use crate::*;
+use ::rowan::{Language, NodeOrToken, SyntaxNode, SyntaxToken, TextRange};
+use std::ops::Range;
+
+impl GetByteRange for TextRange {
+    fn byte_range(&self) -> Range<usize> {
+        self.start().into()..self.end().into()
+    }
+}
+
+impl<L: Language> GetByteRange for SyntaxNode<L> {
+    fn byte_range(&self) -> Range<usize> {
+        self.text_range().byte_range()
+    }
+}
+
+impl<L: Language> GetByteRange for SyntaxToken<L> {
+    fn byte_range(&self) -> Range<usize> {
+        self.text_range().byte_range()
+    }
+}
+
+impl<N: GetByteRange, T: GetByteRange> GetByteRange for NodeOrToken<N, T> {
+    fn byte_range(&self) -> Range<usize> {
+        match self {
+            NodeOrToken::Node(v) => v.byte_range(),
+            NodeOrToken::Token(v) => v.byte_range(),
+        }
+    }
+}
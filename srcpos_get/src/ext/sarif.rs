@@ -0,0 +1,228 @@
+//! [SARIF 2.1.0](https://docs.oasis-open.org/sarif/sarif/v2.1.0/sarif-v2.1.0.html) output with [`serde_json`](https://docs.rs/serde_json)
+//!
+//! Regions are 1-based with exclusive end columns counting Unicode code points like [`Loc`]
+//! # Example
+//! ```
+//! # use srcpos_get::*;
+//! use srcpos_get::diagnostic::Severity;
+//! use srcpos_get::ext::sarif::Sarif;
+//!
+//! let mut sarif = Sarif::new("checker");
+//! sarif.add("unused", Severity::Warning, "src/main.x", &locof!(2, 4, 2, 5), "unused variable `a`");
+//! let log = sarif.to_value();
+//! let region = &log["runs"][0]["results"][0]["locations"][0]["physicalLocation"]["region"];
+//! assert_eq!(region["startLine"], 3);
+//! assert_eq!(region["startColumn"], 5);
+//! assert_eq!(region["endColumn"], 6);
+//! ```
+
+use crate::diagnostic::{self, Severity};
+use crate::*;
+use ::serde_json::{json, Value};
+
+/// Level of a severity
+pub fn level(severity: Severity) -> &'static str {
+    match severity {
+        Severity::Help | Severity::Note => "note",
+        Severity::Warning => "warning",
+        Severity::Error => "error",
+    }
+}
+
+/// Region of a node, lines and columns are 1-based
+pub fn region<T: GetLoc + ?Sized>(at: &T) -> Value {
+    let loc = at.loc();
+    json!({
+        "startLine": loc.from.line + 1,
+        "startColumn": loc.from.column + 1,
+        "endLine": loc.to.line + 1,
+        "endColumn": loc.to.column + 1,
+    })
+}
+
+/// URI of a path, `\` become `/`, absolute and UNC paths become `file` URIs,
+/// relative paths stay relative references and reserved chars are percent-encoded
+pub fn uri(path: &str) -> String {
+    let path = path.replace('\\', "/");
+    let b = path.as_bytes();
+    let drive = b.len() >= 2 && b[0].is_ascii_alphabetic() && b[1] == b':';
+    let mut s = String::with_capacity(path.len());
+    let rest = if drive && (b.len() == 2 || b[2] == b'/') {
+        s.push_str("file:///");
+        s.push_str(&path[..2]);
+        &path[2..]
+    } else if path.starts_with("//") {
+        s.push_str("file:");
+        &path[..]
+    } else {
+        if path.starts_with('/') {
+            s.push_str("file://");
+        }
+        &path[..]
+    };
+    for b in rest.bytes() {
+        match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' | b'/' => {
+                s.push(b as char)
+            }
+            _ => s.push_str(&format!("%{:02X}", b)),
+        }
+    }
+    s
+}
+
+/// Physical location of a node in the file at `path`
+pub fn location<T: GetLoc + ?Sized>(path: &str, at: &T) -> Value {
+    json!({
+        "physicalLocation": {
+            "artifactLocation": { "uri": uri(path) },
+            "region": region(at),
+        }
+    })
+}
+
+/// A SARIF log of one run of a tool
+#[derive(Debug, Clone, Default)]
+pub struct Sarif {
+    name: String,
+    version: Option<String>,
+    rules: Vec<String>,
+    results: Vec<Value>,
+}
+
+impl Sarif {
+    /// New log of the tool named `name`
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            ..Self::default()
+        }
+    }
+
+    /// Set the version of the tool
+    pub fn with_version(mut self, version: impl Into<String>) -> Self {
+        self.version = Some(version.into());
+        self
+    }
+
+    /// Number of results
+    pub fn len(&self) -> usize {
+        self.results.len()
+    }
+
+    /// Has no results
+    pub fn is_empty(&self) -> bool {
+        self.results.is_empty()
+    }
+
+    fn rule_index(&mut self, rule_id: &str) -> usize {
+        match self.rules.iter().position(|r| r == rule_id) {
+            Some(i) => i,
+            None => {
+                self.rules.push(rule_id.to_string());
+                self.rules.len() - 1
+            }
+        }
+    }
+
+    fn push(
+        &mut self,
+        rule_id: &str,
+        severity: Severity,
+        message: String,
+        location: Value,
+    ) -> &mut Value {
+        let rule_index = self.rule_index(rule_id);
+        self.results.push(json!({
+            "ruleId": rule_id,
+            "ruleIndex": rule_index,
+            "level": level(severity),
+            "message": { "text": message },
+            "locations": [location],
+        }));
+        self.results.last_mut().unwrap()
+    }
+
+    /// Add a result at a node in the file at `path`
+    pub fn add<T: GetLoc + ?Sized>(
+        &mut self,
+        rule_id: &str,
+        severity: Severity,
+        path: &str,
+        at: &T,
+        message: impl Into<String>,
+    ) -> &mut Self {
+        self.push(rule_id, severity, message.into(), location(path, at));
+        self
+    }
+
+    /// Add a result at a node knowing its file, `None` if the file is not in `map`
+    pub fn add_file<T: GetFileLoc + ?Sized>(
+        &mut self,
+        map: &SourceMap,
+        rule_id: &str,
+        severity: Severity,
+        at: &T,
+        message: impl Into<String>,
+    ) -> Option<&mut Self> {
+        let at = at.file_loc();
+        let path = map.name(at.file)?;
+        Some(self.add(rule_id, severity, path, &at, message))
+    }
+
+    /// Add a [`diagnostic::Diagnostic`] in the file at `path`
+    ///
+    /// Secondary labels become related locations, the message is [`diagnostic::Diagnostic::full_message`]
+    pub fn add_diagnostic(
+        &mut self,
+        rule_id: &str,
+        path: &str,
+        d: &diagnostic::Diagnostic,
+    ) -> &mut Self {
+        let r = self.push(
+            rule_id,
+            d.severity,
+            d.full_message(),
+            location(path, &d.primary),
+        );
+        if !d.secondary.is_empty() {
+            r["relatedLocations"] = d
+                .secondary
+                .iter()
+                .enumerate()
+                .map(|(i, l)| {
+                    let mut v = location(path, l);
+                    v["id"] = json!(i);
+                    v["message"] = json!({ "text": l.message });
+                    v
+                })
+                .collect();
+        }
+        self
+    }
+
+    /// The SARIF document
+    pub fn to_value(&self) -> Value {
+        let mut driver = json!({
+            "name": self.name,
+            "rules": self.rules.iter().map(|id| json!({ "id": id })).collect::<Vec<_>>(),
+        });
+        if let Some(version) = &self.version {
+            driver["version"] = json!(version);
+        }
+        json!({
+            "$schema": "https://json.schemastore.org/sarif-2.1.0.json",
+            "version": "2.1.0",
+            "runs": [{
+                "tool": { "driver": driver },
+                "columnKind": "unicodeCodePoints",
+                "results": self.results,
+            }],
+        })
+    }
+
+    /// The SARIF document as pretty printed JSON
+    pub fn to_json(&self) -> String {
+        ::serde_json::to_string_pretty(&self.to_value()).unwrap()
+    }
+}
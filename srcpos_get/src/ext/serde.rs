@@ -0,0 +1,199 @@
+//! Representations of [`Pos`], [`Loc`] and [`FileLoc`] for [`serde`](https://docs.rs/serde)
+//!
+//! Use them with `#[serde(with = "srcpos_get::ext::serde::compact")]`,
+//! or pick them per field by `#[serde_loc(compact)]` under the `#[serde_loc]` attribute.
+//! Lines and columns are 0-based like Pos
+//!
+//! | | Pos | Loc | FileLoc |
+//! |-|-|-|-|
+//! | [`structure`] | `{"line":3,"column":5}` | `{"from":{..},"to":{..}}` | `{"file":2,"loc":{..}}` |
+//! | [`compact`] | `[3,5]` | `[3,5,4,1]` | `[2,3,5,4,1]` |
+//! | [`string`] | `"3:5"` | `"3:5-4:1"` | `"#2 3:5-4:1"` |
+//! # Example
+//! ```
+//! # use srcpos_get::*;
+//! use serde::{Deserialize, Serialize};
+//!
+//! #[serde_loc]
+//! #[derive(Serialize, Deserialize, PartialEq, Debug)]
+//! struct Ident {
+//!     name: String,
+//!     #[serde_loc(string)]
+//!     loc: Loc,
+//! }
+//!
+//! let a = Ident { name: "a".to_string(), loc: locof!(3, 5, 4, 1) };
+//! let json = serde_json::to_string(&a).unwrap();
+//! assert_eq!(json, r#"{"name":"a","loc":"3:5-4:1"}"#);
+//! assert_eq!(serde_json::from_str::<Ident>(&json).unwrap(), a);
+//! ```
+
+use crate::*;
+use std::convert::TryInto;
+
+#[cfg(feature = "srcpos_get_derive")]
+pub use srcpos_get_derive::serde_loc;
+
+/// A type with the [`compact`] and [`string`] representations
+pub trait Repr: Sized {
+    /// Description of the compact form
+    const COMPACT: &'static str;
+    /// Description of the string form
+    const STRING: &'static str;
+
+    /// Numbers of the compact form
+    fn to_compact(&self) -> Vec<usize>;
+
+    /// Parse the numbers of the compact form
+    fn from_compact(v: &[usize]) -> Option<Self>;
+
+    /// The string form
+    fn to_repr_string(&self) -> String;
+
+    /// Parse the string form
+    fn from_repr_str(s: &str) -> Option<Self>;
+}
+
+impl Repr for Pos {
+    const COMPACT: &'static str = "[line, column]";
+    const STRING: &'static str = "line:column";
+
+    fn to_compact(&self) -> Vec<usize> {
+        vec![self.line, self.column]
+    }
+
+    fn from_compact(v: &[usize]) -> Option<Self> {
+        match *v {
+            [line, column] => Some(Pos::new(line, column)),
+            _ => None,
+        }
+    }
+
+    fn to_repr_string(&self) -> String {
+        format!("{}:{}", self.line, self.column)
+    }
+
+    fn from_repr_str(s: &str) -> Option<Self> {
+        let (line, column) = s.split_once(':')?;
+        Some(Pos::new(line.parse().ok()?, column.parse().ok()?))
+    }
+}
+
+impl Repr for Loc {
+    const COMPACT: &'static str = "[line, column, line, column]";
+    const STRING: &'static str = "line:column-line:column";
+
+    fn to_compact(&self) -> Vec<usize> {
+        vec![
+            self.from.line,
+            self.from.column,
+            self.to.line,
+            self.to.column,
+        ]
+    }
+
+    fn from_compact(v: &[usize]) -> Option<Self> {
+        match *v {
+            [a1, b1, a2, b2] => Some(Loc::new_at(a1, b1, a2, b2)),
+            _ => None,
+        }
+    }
+
+    fn to_repr_string(&self) -> String {
+        format!(
+            "{}-{}",
+            self.from.to_repr_string(),
+            self.to.to_repr_string()
+        )
+    }
+
+    fn from_repr_str(s: &str) -> Option<Self> {
+        let (from, to) = s.split_once('-')?;
+        Some(Loc::new(Pos::from_repr_str(from)?, Pos::from_repr_str(to)?))
+    }
+}
+
+impl Repr for FileLoc {
+    const COMPACT: &'static str = "[file, line, column, line, column]";
+    const STRING: &'static str = "#file line:column-line:column";
+
+    fn to_compact(&self) -> Vec<usize> {
+        let mut v = vec![self.file.index()];
+        v.extend(self.loc.to_compact());
+        v
+    }
+
+    fn from_compact(v: &[usize]) -> Option<Self> {
+        let (file, loc) = v.split_first()?;
+        Some(FileLoc::new(
+            FileId((*file).try_into().ok()?),
+            Loc::from_compact(loc)?,
+        ))
+    }
+
+    fn to_repr_string(&self) -> String {
+        format!("{} {}", self.file, self.loc.to_repr_string())
+    }
+
+    fn from_repr_str(s: &str) -> Option<Self> {
+        let (file, loc) = s.strip_prefix('#')?.split_once(' ')?;
+        Some(FileLoc::new(
+            FileId(file.parse().ok()?),
+            Loc::from_repr_str(loc)?,
+        ))
+    }
+}
+
+/// The struct form, same as the `Serialize` and `Deserialize` impls
+pub mod structure {
+    use ::serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    /// Serialize
+    pub fn serialize<T: Serialize, S: Serializer>(v: &T, s: S) -> Result<S::Ok, S::Error> {
+        v.serialize(s)
+    }
+
+    /// Deserialize
+    pub fn deserialize<'de, T: Deserialize<'de>, D: Deserializer<'de>>(
+        d: D,
+    ) -> Result<T, D::Error> {
+        T::deserialize(d)
+    }
+}
+
+/// The compact form, an array of numbers
+pub mod compact {
+    use super::Repr;
+    use ::serde::de::Error;
+    use ::serde::{Deserialize, Deserializer, Serializer};
+
+    /// Serialize
+    pub fn serialize<T: Repr, S: Serializer>(v: &T, s: S) -> Result<S::Ok, S::Error> {
+        s.collect_seq(v.to_compact())
+    }
+
+    /// Deserialize
+    pub fn deserialize<'de, T: Repr, D: Deserializer<'de>>(d: D) -> Result<T, D::Error> {
+        let v = Vec::<usize>::deserialize(d)?;
+        T::from_compact(&v).ok_or_else(|| D::Error::invalid_length(v.len(), &T::COMPACT))
+    }
+}
+
+/// The string form
+pub mod string {
+    use super::Repr;
+    use ::serde::de::{Error, Unexpected};
+    use ::serde::{Deserialize, Deserializer, Serializer};
+    use std::borrow::Cow;
+
+    /// Serialize
+    pub fn serialize<T: Repr, S: Serializer>(v: &T, s: S) -> Result<S::Ok, S::Error> {
+        s.serialize_str(&v.to_repr_string())
+    }
+
+    /// Deserialize
+    pub fn deserialize<'de, T: Repr, D: Deserializer<'de>>(d: D) -> Result<T, D::Error> {
+        let v = Cow::<str>::deserialize(d)?;
+        T::from_repr_str(&v).ok_or_else(|| D::Error::invalid_value(Unexpected::Str(&v), &T::STRING))
+    }
+}
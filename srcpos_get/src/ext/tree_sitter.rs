@@ -0,0 +1,66 @@
+//! Integration with [`tree-sitter`](https://docs.rs/tree-sitter)
+//!
+//! Columns of tree-sitter count UTF-8 bytes, get Loc counting chars through [`Indexed`]
+//! with the line index of the source
+//! # Example
+//! ```
+//! # use srcpos_get::*;
+//! use srcpos_get::ext::tree_sitter::input_edit;
+//!
+//! let index = LineIndex::new("let 变量 = 1;\n");
+//! let edit = input_edit(&index, locof!(0, 4, 0, 6), "a");
+//! assert_eq!((edit.start_byte, edit.old_end_byte, edit.new_end_byte), (4, 10, 5));
+//! assert_eq!(edit.old_end_position.column, 10);
+//! // tree.edit(&edit);
+//! // let loc = Indexed::new(&index, tree.root_node()).loc();
+//! ```
+
+use crate::*;
+use ::tree_sitter::{InputEdit, Node, Point, Range};
+
+impl GetByteRange for Range {
+    fn byte_range(&self) -> std::ops::Range<usize> {
+        self.start_byte..self.end_byte
+    }
+}
+
+impl GetByteRange for Node<'_> {
+    fn byte_range(&self) -> std::ops::Range<usize> {
+        self.start_byte()..self.end_byte()
+    }
+}
+
+/// Pos of a point, `index` is the line index of the source
+pub fn char_pos(index: &LineIndex, point: Point) -> Pos {
+    let start = index.line_range(point.row).map_or(index.len(), |r| r.start);
+    index.pos(start + point.column)
+}
+
+/// Point of a byte offset
+fn point(index: &LineIndex, offset: usize) -> Point {
+    let row = index.line(offset);
+    Point::new(row, offset - index.line_range(row).map_or(0, |r| r.start))
+}
+
+/// Make the edit replacing `old` by `text`, `index` is the line index of the source before the edit
+///
+/// `old` is clamped like [`LineIndex::clamp_range`]
+pub fn input_edit(index: &LineIndex, old: Loc, text: &str) -> InputEdit {
+    let range = index.clamp_range(old);
+    let start_position = point(index, range.start);
+    let new_end_position = match text.rfind('\n') {
+        Some(i) => Point::new(
+            start_position.row + text.matches('\n').count(),
+            text.len() - i - 1,
+        ),
+        None => Point::new(start_position.row, start_position.column + text.len()),
+    };
+    InputEdit {
+        start_byte: range.start,
+        old_end_byte: range.end,
+        new_end_byte: range.start + text.len(),
+        start_position,
+        old_end_position: point(index, range.end),
+        new_end_position,
+    }
+}
@@ -1,8 +1,26 @@
 pub use srcpos::*;
 
+use std::borrow::Cow;
+use std::cell::RefCell;
+use std::ops::Deref;
+use std::pin::Pin;
+use std::rc::Rc;
+use std::sync::Arc;
+
 #[cfg(feature = "srcpos_get_derive")]
 pub use srcpos_get_derive::*;
 
+pub mod diagnostic;
+pub mod ext;
+mod line_index;
+mod locate;
+mod shift;
+mod source_map;
+pub use line_index::*;
+pub use locate::*;
+pub use shift::*;
+pub use source_map::*;
+
 /// Calculate Loc
 pub trait GetLoc {
     /// Calculate Loc
@@ -15,30 +33,152 @@ pub trait GetPos {
     fn pos(&self) -> Pos;
 }
 
-impl<T: GetLoc> GetLoc for &T {
+/// Calculate Loc, for things that may have no Loc, such as `None` or an empty list
+pub trait TryGetLoc {
+    /// Calculate Loc, `None` if there is nothing to calculate
+    fn try_loc(&self) -> Option<Loc>;
+}
+
+/// Get Loc for rewriting in place
+pub trait GetLocMut {
+    /// Get Loc for rewriting in place
+    fn loc_mut(&mut self) -> &mut Loc;
+}
+
+/// Overwrite Loc
+pub trait SetLoc {
+    /// Overwrite Loc
+    fn set_loc(&mut self, loc: Loc);
+}
+
+/// Calculate the Loc covering both
+/// # Example
+/// ```
+/// # use srcpos_get::*;
+/// let a = merge_loc(locof!(1, 2, 1, 5), locof!(0, 3, 1, 4));
+/// assert_eq!(a, locof!(0, 3, 1, 5));
+/// ```
+pub fn merge_loc(a: Loc, b: Loc) -> Loc {
+    Loc::new(a.from.min(b.from), a.to.max(b.to))
+}
+
+impl<T: GetLoc + ?Sized> GetLoc for &T {
     fn loc(&self) -> Loc {
         (**self).loc()
     }
 }
 
-impl<T: GetLoc> GetLoc for &mut T {
+impl<T: GetLoc + ?Sized> GetLoc for &mut T {
     fn loc(&self) -> Loc {
         (**self).loc()
     }
 }
 
-impl<T: GetPos> GetPos for &T {
+impl<T: GetPos + ?Sized> GetPos for &T {
     fn pos(&self) -> Pos {
         (**self).pos()
     }
 }
 
-impl<T: GetPos> GetPos for &mut T {
+impl<T: GetPos + ?Sized> GetPos for &mut T {
     fn pos(&self) -> Pos {
         (**self).pos()
     }
 }
 
+macro_rules! impl_deref {
+    ($($t:ident),*) => {$(
+        impl<T: GetLoc + ?Sized> GetLoc for $t<T> {
+            fn loc(&self) -> Loc {
+                (**self).loc()
+            }
+        }
+
+        impl<T: GetPos + ?Sized> GetPos for $t<T> {
+            fn pos(&self) -> Pos {
+                (**self).pos()
+            }
+        }
+    )*};
+}
+
+impl_deref!(Box, Rc, Arc);
+
+impl<T: GetLoc + ToOwned + ?Sized> GetLoc for Cow<'_, T> {
+    fn loc(&self) -> Loc {
+        (**self).loc()
+    }
+}
+
+impl<T: GetPos + ToOwned + ?Sized> GetPos for Cow<'_, T> {
+    fn pos(&self) -> Pos {
+        (**self).pos()
+    }
+}
+
+impl<P: Deref> GetLoc for Pin<P>
+where
+    P::Target: GetLoc,
+{
+    fn loc(&self) -> Loc {
+        (**self).loc()
+    }
+}
+
+impl<P: Deref> GetPos for Pin<P>
+where
+    P::Target: GetPos,
+{
+    fn pos(&self) -> Pos {
+        (**self).pos()
+    }
+}
+
+/// Panics if the cell is mutably borrowed
+impl<T: GetLoc + ?Sized> GetLoc for RefCell<T> {
+    fn loc(&self) -> Loc {
+        self.borrow().loc()
+    }
+}
+
+/// Panics if the cell is mutably borrowed
+impl<T: GetPos + ?Sized> GetPos for RefCell<T> {
+    fn pos(&self) -> Pos {
+        self.borrow().pos()
+    }
+}
+
+macro_rules! impl_tuple {
+    ($first:ident $(, $rest:ident)*) => {
+        /// Covering all elements
+        impl<$first: GetLoc, $($rest: GetLoc),*> GetLoc for ($first, $($rest,)*) {
+            #[allow(non_snake_case)]
+            fn loc(&self) -> Loc {
+                let ($first, $($rest,)*) = self;
+                let loc = $first.loc();
+                $(let loc = merge_loc(loc, $rest.loc());)*
+                loc
+            }
+        }
+
+        /// Pos of the first element
+        impl<$first: GetPos, $($rest),*> GetPos for ($first, $($rest,)*) {
+            fn pos(&self) -> Pos {
+                self.0.pos()
+            }
+        }
+    };
+}
+
+impl_tuple!(A);
+impl_tuple!(A, B);
+impl_tuple!(A, B, C);
+impl_tuple!(A, B, C, D);
+impl_tuple!(A, B, C, D, E);
+impl_tuple!(A, B, C, D, E, F);
+impl_tuple!(A, B, C, D, E, F, G);
+impl_tuple!(A, B, C, D, E, F, G, H);
+
 impl GetLoc for Loc {
     fn loc(&self) -> Loc {
         *self
@@ -56,3 +196,90 @@ impl GetPos for Loc {
         self.from
     }
 }
+
+impl GetLocMut for Loc {
+    fn loc_mut(&mut self) -> &mut Loc {
+        self
+    }
+}
+
+impl<T: GetLocMut + ?Sized> GetLocMut for &mut T {
+    fn loc_mut(&mut self) -> &mut Loc {
+        (**self).loc_mut()
+    }
+}
+
+impl<T: GetLocMut + ?Sized> GetLocMut for Box<T> {
+    fn loc_mut(&mut self) -> &mut Loc {
+        (**self).loc_mut()
+    }
+}
+
+impl SetLoc for Loc {
+    fn set_loc(&mut self, loc: Loc) {
+        *self = loc
+    }
+}
+
+impl<T: SetLoc + ?Sized> SetLoc for &mut T {
+    fn set_loc(&mut self, loc: Loc) {
+        (**self).set_loc(loc)
+    }
+}
+
+impl<T: SetLoc + ?Sized> SetLoc for Box<T> {
+    fn set_loc(&mut self, loc: Loc) {
+        (**self).set_loc(loc)
+    }
+}
+
+impl<T: SetLoc + ?Sized> SetLoc for RefCell<T> {
+    fn set_loc(&mut self, loc: Loc) {
+        self.get_mut().set_loc(loc)
+    }
+}
+
+/// Does nothing if `None`
+impl<T: SetLoc> SetLoc for Option<T> {
+    fn set_loc(&mut self, loc: Loc) {
+        if let Some(v) = self {
+            v.set_loc(loc)
+        }
+    }
+}
+
+impl<T: GetLoc + ?Sized> TryGetLoc for T {
+    fn try_loc(&self) -> Option<Loc> {
+        Some(self.loc())
+    }
+}
+
+impl<T: TryGetLoc> TryGetLoc for Option<T> {
+    fn try_loc(&self) -> Option<Loc> {
+        self.as_ref().and_then(|v| v.try_loc())
+    }
+}
+
+/// From the start of the first element with Loc to the end of the last one
+impl<T: TryGetLoc> TryGetLoc for [T] {
+    fn try_loc(&self) -> Option<Loc> {
+        let first = self.iter().find_map(|v| v.try_loc())?;
+        let last = self.iter().rev().find_map(|v| v.try_loc())?;
+        Some(Loc::new(first.from, last.to))
+    }
+}
+
+/// From the start of the first element with Loc to the end of the last one,
+/// arrays have no GetLoc so that empty arrays are `None`
+impl<T: TryGetLoc, const N: usize> TryGetLoc for [T; N] {
+    fn try_loc(&self) -> Option<Loc> {
+        self.as_slice().try_loc()
+    }
+}
+
+/// From the start of the first element with Loc to the end of the last one
+impl<T: TryGetLoc> TryGetLoc for Vec<T> {
+    fn try_loc(&self) -> Option<Loc> {
+        self.as_slice().try_loc()
+    }
+}
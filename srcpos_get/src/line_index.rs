@@ -0,0 +1,352 @@
+use crate::*;
+use std::ops::Range;
+
+/// A non-ASCII char
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub(crate) struct WideChar {
+    /// Byte offset in the text
+    pub offset: usize,
+    /// Length in UTF-8
+    pub len: usize,
+    /// Sum of `len - 1` of all chars before
+    pub extra: usize,
+}
+
+impl WideChar {
+    #[inline]
+    pub fn end(&self) -> usize {
+        self.offset + self.len
+    }
+}
+
+/// Convert between byte offsets and Pos
+///
+/// Lines are split by `\n`, columns count chars, both start from 0
+/// # Example
+/// ```
+/// # use srcpos_get::*;
+/// let index = LineIndex::new("let a = 1;\nlet 变量 = 2;");
+/// assert_eq!(index.pos(15), pos(1, 4));
+/// assert_eq!(index.offset(pos(1, 6)), Some(21));
+/// assert_eq!(index.loc(15..21), locof!(1, 4, 1, 6));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct LineIndex {
+    /// Byte offset of the start of every line
+    line_starts: Vec<usize>,
+    /// Lines ending with `\r\n`, sorted
+    crlf_lines: Vec<usize>,
+    /// All non-ASCII chars, sorted by offset
+    wide_chars: Vec<WideChar>,
+    len: usize,
+}
+
+impl LineIndex {
+    /// Build from source text
+    pub fn new(text: &str) -> Self {
+        let mut line_starts = vec![0];
+        let mut crlf_lines = vec![];
+        let mut wide_chars = vec![];
+        let mut extra = 0;
+        for (i, c) in text.char_indices() {
+            if c == '\n' {
+                if text[..i].ends_with('\r') {
+                    crlf_lines.push(line_starts.len() - 1);
+                }
+                line_starts.push(i + 1);
+            } else if !c.is_ascii() {
+                wide_chars.push(WideChar {
+                    offset: i,
+                    len: c.len_utf8(),
+                    extra,
+                });
+                extra += c.len_utf8() - 1;
+            }
+        }
+        Self {
+            line_starts,
+            crlf_lines,
+            wide_chars,
+            len: text.len(),
+        }
+    }
+
+    /// Length of the text in bytes
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// The text is empty
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Number of lines, an empty text has 1 line
+    #[inline]
+    pub fn line_count(&self) -> usize {
+        self.line_starts.len()
+    }
+
+    /// Byte range of a line, without the `\n` or `\r\n`
+    pub fn line_range(&self, line: usize) -> Option<Range<usize>> {
+        let start = *self.line_starts.get(line)?;
+        let end = match self.line_starts.get(line + 1) {
+            Some(next) if self.crlf_lines.binary_search(&line).is_ok() => next - 2,
+            Some(next) => next - 1,
+            None => self.len,
+        };
+        Some(start..end)
+    }
+
+    /// Non-ASCII chars in a byte range
+    pub(crate) fn wide_chars(&self, range: Range<usize>) -> &[WideChar] {
+        let start = self.wide_chars.partition_point(|c| c.offset < range.start);
+        let end = self.wide_chars.partition_point(|c| c.offset < range.end);
+        &self.wide_chars[start..end]
+    }
+
+    /// Line of a byte offset, offsets after the end are in the last line
+    pub fn line(&self, offset: usize) -> usize {
+        self.line_starts.partition_point(|&s| s <= offset) - 1
+    }
+
+    /// Column of a byte offset, `width` is the length of a char in the column encoding
+    fn column_by(&self, line: usize, offset: usize, width: impl Fn(&WideChar) -> usize) -> usize {
+        let start = self.line_starts[line];
+        let mut column = offset - start;
+        for c in self.wide_chars(start..offset) {
+            if c.end() <= offset {
+                column -= c.len - width(c);
+            } else {
+                column -= offset - c.offset;
+            }
+        }
+        column
+    }
+
+    /// Byte offset of a column, `width` is the length of a char in the column encoding
+    fn offset_by(&self, pos: Pos, width: impl Fn(&WideChar) -> usize) -> Option<usize> {
+        let range = self.line_range(pos.line)?;
+        let mut offset = range.start + pos.column;
+        for c in self.wide_chars(range.clone()) {
+            if c.offset >= offset {
+                break;
+            }
+            offset += c.len - width(c);
+        }
+        if offset > range.end {
+            return None;
+        }
+        Some(offset)
+    }
+
+    /// Convert a byte offset to Pos
+    ///
+    /// Offsets after the end are clamped to the end, offsets inside a char are at that char
+    pub fn pos(&self, offset: usize) -> Pos {
+        let offset = offset.min(self.len);
+        let line = self.line(offset);
+        Pos::new(line, self.column_by(line, offset, |_| 1))
+    }
+
+    /// Convert a Pos to byte offset, `None` if it is not in the text
+    pub fn offset(&self, pos: Pos) -> Option<usize> {
+        self.offset_by(pos, |_| 1)
+    }
+
+    /// Convert a Pos to byte offset, lines after the end are clamped to the end of the text,
+    /// columns after the end of the line are clamped to the end of the line
+    pub fn clamp_offset(&self, pos: Pos) -> usize {
+        match self.line_range(pos.line) {
+            Some(range) => self.offset(pos).unwrap_or(range.end),
+            None => self.len,
+        }
+    }
+
+    /// Convert a Loc to byte range, clamped like [`clamp_offset`](Self::clamp_offset)
+    pub fn clamp_range(&self, loc: Loc) -> Range<usize> {
+        let from = self.clamp_offset(loc.from);
+        from..self.clamp_offset(loc.to).max(from)
+    }
+
+    /// Number of chars before a byte offset, offsets inside a char are at that char
+    pub fn char_offset(&self, offset: usize) -> usize {
+        let offset = offset.min(self.len);
+        let i = self.wide_chars.partition_point(|c| c.offset < offset);
+        match i.checked_sub(1).map(|i| &self.wide_chars[i]) {
+            Some(c) if c.end() > offset => c.offset - c.extra,
+            Some(c) => offset - c.extra - (c.len - 1),
+            None => offset,
+        }
+    }
+
+    /// Convert a byte range to Loc
+    pub fn loc(&self, range: Range<usize>) -> Loc {
+        Loc::new(self.pos(range.start), self.pos(range.end))
+    }
+
+    /// Convert a Loc to byte range, `None` if it is not in the text
+    pub fn range(&self, loc: Loc) -> Option<Range<usize>> {
+        Some(self.offset(loc.from)?..self.offset(loc.to)?)
+    }
+}
+
+/// Byte range in a text, converted to Loc through [`Indexed`]
+pub trait GetByteRange {
+    /// Byte range in the text
+    fn byte_range(&self) -> Range<usize>;
+}
+
+impl GetByteRange for Range<usize> {
+    fn byte_range(&self) -> Range<usize> {
+        self.clone()
+    }
+}
+
+impl<T: GetByteRange + ?Sized> GetByteRange for &T {
+    fn byte_range(&self) -> Range<usize> {
+        (**self).byte_range()
+    }
+}
+
+/// A value with a byte range and the line index of its text
+/// # Example
+/// ```
+/// # use srcpos_get::*;
+/// let index = LineIndex::new("let 变量 = 1;");
+/// assert_eq!(Indexed::new(&index, 4..10).loc(), locof!(0, 4, 0, 6));
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct Indexed<'a, T> {
+    /// Line index of the text
+    pub index: &'a LineIndex,
+    /// The value
+    pub inner: T,
+}
+
+impl<'a, T> Indexed<'a, T> {
+    /// New
+    pub fn new(index: &'a LineIndex, inner: T) -> Self {
+        Self { index, inner }
+    }
+}
+
+impl<T: GetByteRange> GetLoc for Indexed<'_, T> {
+    fn loc(&self) -> Loc {
+        self.index.loc(self.inner.byte_range())
+    }
+}
+
+impl<T: GetByteRange> GetPos for Indexed<'_, T> {
+    fn pos(&self) -> Pos {
+        self.index.pos(self.inner.byte_range().start)
+    }
+}
+
+/// How columns are counted
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum ColumnEncoding {
+    /// UTF-8 bytes
+    Utf8,
+    /// UTF-16 code units, the default of the Language Server Protocol
+    Utf16,
+    /// Unicode scalar values, the columns of Pos in this crate
+    #[default]
+    Utf32,
+}
+
+impl ColumnEncoding {
+    /// Length of a char
+    #[inline]
+    pub fn len(self, c: char) -> usize {
+        match self {
+            ColumnEncoding::Utf8 => c.len_utf8(),
+            ColumnEncoding::Utf16 => c.len_utf16(),
+            ColumnEncoding::Utf32 => 1,
+        }
+    }
+
+    fn width(self, c: &WideChar) -> usize {
+        match self {
+            ColumnEncoding::Utf8 => c.len,
+            ColumnEncoding::Utf16 if c.len == 4 => 2,
+            _ => 1,
+        }
+    }
+}
+
+impl LineIndex {
+    /// Convert a Pos to a Pos whose column is counted in `encoding`, `None` if it is not in the text
+    /// # Example
+    /// ```
+    /// # use srcpos_get::*;
+    /// let index = LineIndex::new("a😀b");
+    /// assert_eq!(index.to_encoding(pos(0, 2), ColumnEncoding::Utf8), Some(pos(0, 5)));
+    /// assert_eq!(index.to_encoding(pos(0, 2), ColumnEncoding::Utf16), Some(pos(0, 3)));
+    /// ```
+    pub fn to_encoding(&self, pos: Pos, encoding: ColumnEncoding) -> Option<Pos> {
+        let offset = self.offset(pos)?;
+        let column = self.column_by(pos.line, offset, |c| encoding.width(c));
+        Some(Pos::new(pos.line, column))
+    }
+
+    /// Convert a Pos whose column is counted in `encoding` to a Pos, `None` if it is not in the text
+    ///
+    /// A column inside a char is at that char
+    /// # Example
+    /// ```
+    /// # use srcpos_get::*;
+    /// let index = LineIndex::new("a😀b");
+    /// assert_eq!(index.from_encoding(pos(0, 5), ColumnEncoding::Utf8), Some(pos(0, 2)));
+    /// assert_eq!(index.from_encoding(pos(0, 3), ColumnEncoding::Utf16), Some(pos(0, 2)));
+    /// ```
+    pub fn from_encoding(&self, pos: Pos, encoding: ColumnEncoding) -> Option<Pos> {
+        let offset = self.offset_by(pos, |c| encoding.width(c))?;
+        Some(self.pos(offset))
+    }
+
+    /// Convert a Pos to a Pos whose column counts extended grapheme clusters,
+    /// `None` if it is not in the text
+    ///
+    /// Pos inside a cluster are at that cluster
+    ///
+    /// `text` must be the text this index is built from
+    /// # Example
+    /// ```
+    /// # use srcpos_get::*;
+    /// let text = "e\u{301}x";
+    /// let index = LineIndex::new(text);
+    /// assert_eq!(index.to_grapheme(text, pos(0, 2)), Some(pos(0, 1)));
+    /// assert_eq!(index.from_grapheme(text, pos(0, 1)), Some(pos(0, 2)));
+    /// ```
+    #[cfg(feature = "grapheme")]
+    pub fn to_grapheme(&self, text: &str, pos: Pos) -> Option<Pos> {
+        use unicode_segmentation::UnicodeSegmentation;
+        let range = self.line_range(pos.line)?;
+        let offset = self.offset(pos)? - range.start;
+        let column = text[range]
+            .grapheme_indices(true)
+            .take_while(|(i, g)| i + g.len() <= offset)
+            .count();
+        Some(Pos::new(pos.line, column))
+    }
+
+    /// Convert a Pos whose column counts extended grapheme clusters to a Pos,
+    /// `None` if it is not in the text
+    ///
+    /// `text` must be the text this index is built from
+    #[cfg(feature = "grapheme")]
+    pub fn from_grapheme(&self, text: &str, pos: Pos) -> Option<Pos> {
+        use unicode_segmentation::UnicodeSegmentation;
+        let range = self.line_range(pos.line)?;
+        let line = &text[range.clone()];
+        let offset = match line.grapheme_indices(true).nth(pos.column) {
+            Some((i, _)) => i,
+            None if pos.column == line.graphemes(true).count() => line.len(),
+            None => return None,
+        };
+        Some(self.pos(range.start + offset))
+    }
+}
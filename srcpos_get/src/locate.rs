@@ -0,0 +1,87 @@
+use crate::*;
+use std::rc::Rc;
+use std::sync::Arc;
+
+/// Whether `pos` is in `loc`, the end is exclusive
+/// # Example
+/// ```
+/// # use srcpos_get::*;
+/// assert!(loc_contains(locof!(0, 1, 0, 3), pos(0, 1)));
+/// assert!(!loc_contains(locof!(0, 1, 0, 3), pos(0, 3)));
+/// ```
+pub fn loc_contains(loc: Loc, pos: Pos) -> bool {
+    loc.from <= pos && pos < loc.to
+}
+
+/// Find the innermost node at a Pos
+pub trait Locate {
+    /// Push the nodes containing `pos` into `path`, from the outermost to the innermost,
+    /// returns whether anything was found
+    fn locate_into<'a>(&'a self, pos: Pos, path: &mut Vec<&'a dyn GetLoc>) -> bool;
+
+    /// The nodes containing `pos`, from the root to the innermost
+    fn locate(&self, pos: Pos) -> Vec<&dyn GetLoc> {
+        let mut path = vec![];
+        self.locate_into(pos, &mut path);
+        path
+    }
+
+    /// The innermost node containing `pos`
+    fn node_at(&self, pos: Pos) -> Option<&dyn GetLoc> {
+        self.locate(pos).pop()
+    }
+}
+
+impl<T: Locate + ?Sized> Locate for &T {
+    fn locate_into<'a>(&'a self, pos: Pos, path: &mut Vec<&'a dyn GetLoc>) -> bool {
+        (**self).locate_into(pos, path)
+    }
+}
+
+impl<T: Locate + ?Sized> Locate for Box<T> {
+    fn locate_into<'a>(&'a self, pos: Pos, path: &mut Vec<&'a dyn GetLoc>) -> bool {
+        (**self).locate_into(pos, path)
+    }
+}
+
+impl<T: Locate + ?Sized> Locate for Rc<T> {
+    fn locate_into<'a>(&'a self, pos: Pos, path: &mut Vec<&'a dyn GetLoc>) -> bool {
+        (**self).locate_into(pos, path)
+    }
+}
+
+impl<T: Locate + ?Sized> Locate for Arc<T> {
+    fn locate_into<'a>(&'a self, pos: Pos, path: &mut Vec<&'a dyn GetLoc>) -> bool {
+        (**self).locate_into(pos, path)
+    }
+}
+
+impl<T: Locate> Locate for Option<T> {
+    fn locate_into<'a>(&'a self, pos: Pos, path: &mut Vec<&'a dyn GetLoc>) -> bool {
+        match self {
+            Some(v) => v.locate_into(pos, path),
+            None => false,
+        }
+    }
+}
+
+/// The first element containing `pos`
+impl<T: Locate> Locate for [T] {
+    fn locate_into<'a>(&'a self, pos: Pos, path: &mut Vec<&'a dyn GetLoc>) -> bool {
+        self.iter().any(|v| v.locate_into(pos, path))
+    }
+}
+
+/// The first element containing `pos`
+impl<T: Locate, const N: usize> Locate for [T; N] {
+    fn locate_into<'a>(&'a self, pos: Pos, path: &mut Vec<&'a dyn GetLoc>) -> bool {
+        self.as_slice().locate_into(pos, path)
+    }
+}
+
+/// The first element containing `pos`
+impl<T: Locate> Locate for Vec<T> {
+    fn locate_into<'a>(&'a self, pos: Pos, path: &mut Vec<&'a dyn GetLoc>) -> bool {
+        self.as_slice().locate_into(pos, path)
+    }
+}
@@ -0,0 +1,215 @@
+use crate::*;
+use std::borrow::Cow;
+use std::collections::VecDeque;
+
+/// Visit every Pos recursively
+pub trait VisitLocMut {
+    /// Call `f` on every Pos, both `from` and `to` of every Loc
+    fn visit_loc_mut(&mut self, f: &mut dyn FnMut(&mut Pos));
+}
+
+/// Shift every Loc after an edit
+pub trait ShiftLoc {
+    /// Shift every Loc after an edit
+    fn shift_loc(&mut self, edit: &TextEdit);
+}
+
+impl<T: VisitLocMut + ?Sized> ShiftLoc for T {
+    fn shift_loc(&mut self, edit: &TextEdit) {
+        self.visit_loc_mut(&mut |pos| *pos = edit.apply(*pos))
+    }
+}
+
+/// Pos at the end of `text` starting at `from`
+pub(crate) fn advance(from: Pos, text: &str) -> Pos {
+    let mut to = from;
+    for c in text.chars() {
+        if c == '\n' {
+            to.line += 1;
+            to.column = 0;
+        } else {
+            to.column += 1;
+        }
+    }
+    to
+}
+
+/// A text edit, the text in `from..old_to` is replaced by text ending at `new_to`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct TextEdit {
+    /// Start of the edit
+    pub from: Pos,
+    /// End of the replaced text
+    pub old_to: Pos,
+    /// End of the new text
+    pub new_to: Pos,
+}
+
+impl TextEdit {
+    /// New edit
+    pub const fn new(from: Pos, old_to: Pos, new_to: Pos) -> Self {
+        Self {
+            from,
+            old_to,
+            new_to,
+        }
+    }
+
+    /// Replace the text in `old` by `text`
+    /// # Example
+    /// ```
+    /// # use srcpos_get::*;
+    /// let edit = TextEdit::replace(locof!(1, 2, 1, 4), "a\nbc");
+    /// assert_eq!(edit.new_to, pos(2, 2));
+    /// ```
+    pub fn replace(old: Loc, text: &str) -> Self {
+        Self::new(old.from, old.to, advance(old.from, text))
+    }
+
+    /// Insert `text` at `at`
+    pub fn insert(at: Pos, text: &str) -> Self {
+        Self::replace(Loc::new_same_pos(at), text)
+    }
+
+    /// Shift a Pos
+    ///
+    /// Pos before the edit are unchanged, Pos after the replaced text move with it,
+    /// Pos inside the replaced text are clamped to the end of the new text
+    /// # Example
+    /// ```
+    /// # use srcpos_get::*;
+    /// let edit = TextEdit::insert(pos(1, 2), "a\nbc");
+    /// assert_eq!(edit.apply(pos(0, 5)), pos(0, 5));
+    /// assert_eq!(edit.apply(pos(1, 3)), pos(2, 3));
+    /// assert_eq!(edit.apply(pos(3, 1)), pos(4, 1));
+    /// ```
+    pub fn apply(&self, pos: Pos) -> Pos {
+        if pos < self.from {
+            pos
+        } else if pos < self.old_to {
+            pos.min(self.new_to)
+        } else if pos.line == self.old_to.line {
+            Pos::new(
+                self.new_to.line,
+                self.new_to.column + (pos.column - self.old_to.column),
+            )
+        } else {
+            Pos::new(pos.line - self.old_to.line + self.new_to.line, pos.column)
+        }
+    }
+}
+
+impl VisitLocMut for Pos {
+    fn visit_loc_mut(&mut self, f: &mut dyn FnMut(&mut Pos)) {
+        f(self)
+    }
+}
+
+impl VisitLocMut for Loc {
+    fn visit_loc_mut(&mut self, f: &mut dyn FnMut(&mut Pos)) {
+        f(&mut self.from);
+        f(&mut self.to);
+    }
+}
+
+impl<T: VisitLocMut + ?Sized> VisitLocMut for &mut T {
+    fn visit_loc_mut(&mut self, f: &mut dyn FnMut(&mut Pos)) {
+        (**self).visit_loc_mut(f)
+    }
+}
+
+impl<T: VisitLocMut + ?Sized> VisitLocMut for Box<T> {
+    fn visit_loc_mut(&mut self, f: &mut dyn FnMut(&mut Pos)) {
+        (**self).visit_loc_mut(f)
+    }
+}
+
+impl<T: VisitLocMut> VisitLocMut for Option<T> {
+    fn visit_loc_mut(&mut self, f: &mut dyn FnMut(&mut Pos)) {
+        if let Some(v) = self {
+            v.visit_loc_mut(f)
+        }
+    }
+}
+
+impl<T: VisitLocMut> VisitLocMut for [T] {
+    fn visit_loc_mut(&mut self, f: &mut dyn FnMut(&mut Pos)) {
+        for v in self {
+            v.visit_loc_mut(f)
+        }
+    }
+}
+
+impl<T: VisitLocMut, const N: usize> VisitLocMut for [T; N] {
+    fn visit_loc_mut(&mut self, f: &mut dyn FnMut(&mut Pos)) {
+        self.as_mut_slice().visit_loc_mut(f)
+    }
+}
+
+impl<T: VisitLocMut> VisitLocMut for Vec<T> {
+    fn visit_loc_mut(&mut self, f: &mut dyn FnMut(&mut Pos)) {
+        self.as_mut_slice().visit_loc_mut(f)
+    }
+}
+
+impl<T: VisitLocMut> VisitLocMut for VecDeque<T> {
+    fn visit_loc_mut(&mut self, f: &mut dyn FnMut(&mut Pos)) {
+        for v in self {
+            v.visit_loc_mut(f)
+        }
+    }
+}
+
+macro_rules! impl_tuple {
+    ($($t:ident),*) => {
+        impl<$($t: VisitLocMut),*> VisitLocMut for ($($t,)*) {
+            #[allow(non_snake_case)]
+            fn visit_loc_mut(&mut self, f: &mut dyn FnMut(&mut Pos)) {
+                let ($($t,)*) = self;
+                $($t.visit_loc_mut(f);)*
+            }
+        }
+    };
+}
+
+impl_tuple!(A);
+impl_tuple!(A, B);
+impl_tuple!(A, B, C);
+impl_tuple!(A, B, C, D);
+impl_tuple!(A, B, C, D, E);
+impl_tuple!(A, B, C, D, E, F);
+impl_tuple!(A, B, C, D, E, F, G);
+impl_tuple!(A, B, C, D, E, F, G, H);
+
+macro_rules! impl_nothing {
+    ($($t:ty),*) => {$(
+        /// Has no Pos
+        impl VisitLocMut for $t {
+            fn visit_loc_mut(&mut self, _: &mut dyn FnMut(&mut Pos)) {}
+        }
+    )*};
+}
+
+impl_nothing!(
+    (),
+    bool,
+    char,
+    u8,
+    u16,
+    u32,
+    u64,
+    u128,
+    usize,
+    i8,
+    i16,
+    i32,
+    i64,
+    i128,
+    isize,
+    f32,
+    f64,
+    str,
+    String,
+    &str,
+    Cow<'_, str>
+);
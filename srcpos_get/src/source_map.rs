@@ -0,0 +1,230 @@
+use crate::*;
+use std::fmt;
+use std::rc::Rc;
+use std::sync::Arc;
+
+/// Id of a file in a [`SourceMap`]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Default)]
+pub struct FileId(pub u32);
+
+impl FileId {
+    /// Index in the [`SourceMap`]
+    #[inline]
+    pub const fn index(self) -> usize {
+        self.0 as usize
+    }
+}
+
+impl fmt::Display for FileId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "#{}", self.0)
+    }
+}
+
+/// A file in a [`SourceMap`]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct SourceFile {
+    name: String,
+    source: String,
+    line_index: LineIndex,
+}
+
+impl SourceFile {
+    /// File name
+    #[inline]
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// File contents
+    #[inline]
+    pub fn source(&self) -> &str {
+        &self.source
+    }
+
+    /// Line index of the contents
+    #[inline]
+    pub fn line_index(&self) -> &LineIndex {
+        &self.line_index
+    }
+}
+
+/// Stores file names and contents
+/// # Example
+/// ```
+/// # use srcpos_get::*;
+/// let mut map = SourceMap::new();
+/// let a = map.add("a.txt", "a");
+/// let b = map.add("b.txt", "b");
+/// assert_ne!(a, b);
+/// assert_eq!(map.name(b), Some("b.txt"));
+/// assert_eq!(map.source(a), Some("a"));
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct SourceMap {
+    files: Vec<SourceFile>,
+}
+
+impl SourceMap {
+    /// New empty
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a file
+    pub fn add(&mut self, name: impl Into<String>, source: impl Into<String>) -> FileId {
+        let id = FileId(self.files.len() as u32);
+        let source = source.into();
+        self.files.push(SourceFile {
+            name: name.into(),
+            line_index: LineIndex::new(&source),
+            source,
+        });
+        id
+    }
+
+    /// Get a file
+    #[inline]
+    pub fn get(&self, id: FileId) -> Option<&SourceFile> {
+        self.files.get(id.index())
+    }
+
+    /// File name
+    #[inline]
+    pub fn name(&self, id: FileId) -> Option<&str> {
+        self.get(id).map(|f| f.name())
+    }
+
+    /// File contents
+    #[inline]
+    pub fn source(&self, id: FileId) -> Option<&str> {
+        self.get(id).map(|f| f.source())
+    }
+
+    /// Line index of the file contents
+    #[inline]
+    pub fn line_index(&self, id: FileId) -> Option<&LineIndex> {
+        self.get(id).map(|f| f.line_index())
+    }
+
+    /// Iterate all files
+    pub fn iter(&self) -> impl Iterator<Item = (FileId, &SourceFile)> {
+        self.files
+            .iter()
+            .enumerate()
+            .map(|(i, f)| (FileId(i as u32), f))
+    }
+
+    /// Number of files
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.files.len()
+    }
+
+    /// No files
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.files.is_empty()
+    }
+}
+
+/// Loc in a file
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Default)]
+pub struct FileLoc {
+    /// file
+    pub file: FileId,
+    /// loc
+    pub loc: Loc,
+}
+
+impl FileLoc {
+    /// New at
+    #[inline]
+    pub const fn new(file: FileId, loc: Loc) -> Self {
+        Self { file, loc }
+    }
+}
+
+impl fmt::Display for FileLoc {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}", self.file, self.loc)
+    }
+}
+
+impl GetLoc for FileLoc {
+    fn loc(&self) -> Loc {
+        self.loc
+    }
+}
+
+impl GetPos for FileLoc {
+    fn pos(&self) -> Pos {
+        self.loc.from
+    }
+}
+
+impl GetLocMut for FileLoc {
+    fn loc_mut(&mut self) -> &mut Loc {
+        &mut self.loc
+    }
+}
+
+impl SetLoc for FileLoc {
+    fn set_loc(&mut self, loc: Loc) {
+        self.loc = loc
+    }
+}
+
+impl VisitLocMut for FileLoc {
+    fn visit_loc_mut(&mut self, f: &mut dyn FnMut(&mut Pos)) {
+        self.loc.visit_loc_mut(f)
+    }
+}
+
+impl VisitLocMut for FileId {
+    fn visit_loc_mut(&mut self, _: &mut dyn FnMut(&mut Pos)) {}
+}
+
+/// Calculate FileLoc
+pub trait GetFileLoc {
+    /// Calculate FileLoc
+    fn file_loc(&self) -> FileLoc;
+}
+
+impl GetFileLoc for FileLoc {
+    fn file_loc(&self) -> FileLoc {
+        *self
+    }
+}
+
+impl<T: GetFileLoc + ?Sized> GetFileLoc for &T {
+    fn file_loc(&self) -> FileLoc {
+        (**self).file_loc()
+    }
+}
+
+impl<T: GetFileLoc + ?Sized> GetFileLoc for &mut T {
+    fn file_loc(&self) -> FileLoc {
+        (**self).file_loc()
+    }
+}
+
+impl<T: GetFileLoc + ?Sized> GetFileLoc for Box<T> {
+    fn file_loc(&self) -> FileLoc {
+        (**self).file_loc()
+    }
+}
+
+impl<T: GetFileLoc + ?Sized> GetFileLoc for Rc<T> {
+    fn file_loc(&self) -> FileLoc {
+        (**self).file_loc()
+    }
+}
+
+impl<T: GetFileLoc + ?Sized> GetFileLoc for Arc<T> {
+    fn file_loc(&self) -> FileLoc {
+        (**self).file_loc()
+    }
+}
@@ -0,0 +1,35 @@
+#![cfg(feature = "annotate-snippets")]
+
+use annotate_snippets::{AnnotationKind, Level, Renderer};
+use srcpos_get::ext::annotate_snippets::*;
+use srcpos_get::*;
+
+#[test]
+fn test_multi_line() {
+    let mut map = SourceMap::new();
+    let file = map.add("a.x", "fn f() {\n    é\n}\n");
+    let report = &[Level::ERROR.primary_title("bad body").element(
+        file_snippet(
+            &map,
+            file,
+            vec![
+                (locof!(0, 7, 2, 1), "this block", AnnotationKind::Primary),
+                (locof!(1, 4, 1, 5), "here", AnnotationKind::Context),
+            ],
+        )
+        .unwrap(),
+    )];
+    assert_eq!(
+        Renderer::plain().render(report),
+        "\
+error: bad body
+ --> a.x:1:8
+  |
+1 |   fn f() {
+  |  ________^
+2 | |     é
+  | |     - here
+3 | | }
+  | |_^ this block"
+    );
+}
@@ -0,0 +1,30 @@
+#![cfg(feature = "ariadne")]
+
+use ariadne::{Config, Report, ReportKind, Span};
+use srcpos_get::ext::ariadne::*;
+use srcpos_get::*;
+
+#[test]
+fn test_span() {
+    let index = LineIndex::new("aé\n😀bc\n");
+    let span = LocSpan::new("a", &index, &locof!(1, 1, 1, 3));
+    assert_eq!((span.start(), span.end()), (4, 6));
+    assert_eq!(*span.source(), "a");
+}
+
+#[test]
+fn test_report() {
+    let mut map = SourceMap::new();
+    let file = map.add("a.x", "let é = b;\n");
+    let span = file_span(&map, &FileLoc::new(file, locof!(0, 8, 0, 9))).unwrap();
+    let mut out = vec![];
+    Report::build(ReportKind::Error, span.clone())
+        .with_config(Config::default().with_color(false))
+        .with_message("cannot find value `b`")
+        .with_label(label(file, map.line_index(file).unwrap(), &span).with_message("here"))
+        .finish()
+        .write(cache(&map), &mut out)
+        .unwrap();
+    let out = String::from_utf8(out).unwrap();
+    assert!(out.contains("a.x:1:9"), "{}", out);
+}
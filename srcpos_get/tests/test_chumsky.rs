@@ -0,0 +1,31 @@
+#![cfg(feature = "chumsky")]
+
+use chumsky::prelude::*;
+use srcpos_get::ext::chumsky::map_span;
+use srcpos_get::*;
+
+#[test]
+fn test_span() {
+    let mut map = SourceMap::new();
+    map.add("a.x", "");
+    let file = map.add("b.x", "(变量\n  x)");
+    let index = map.line_index(file).unwrap();
+    let input = map.source(file).unwrap().map_span(map_span(file, index));
+    let word = any::<_, extra::Default>()
+        .filter(|c: &char| c.is_alphabetic())
+        .repeated()
+        .at_least(1)
+        .to_slice()
+        .map_with(|_, e| e.span())
+        .padded();
+    let list = word
+        .repeated()
+        .collect::<Vec<FileLoc>>()
+        .delimited_by(just('('), just(')'))
+        .map_with(|words, e| (words, e.span()));
+    let (words, all): (Vec<FileLoc>, FileLoc) = list.parse(input).unwrap();
+    assert_eq!(words[0], FileLoc::new(file, locof!(0, 1, 0, 3)));
+    assert_eq!(words[1].loc(), locof!(1, 2, 1, 3));
+    assert_eq!(all.loc(), locof!(0, 0, 1, 4));
+    assert_eq!(words[0].union(words[1]).loc(), locof!(0, 1, 1, 3));
+}
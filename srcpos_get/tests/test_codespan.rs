@@ -0,0 +1,36 @@
+#![cfg(feature = "codespan")]
+
+use codespan_reporting::diagnostic::Severity;
+use codespan_reporting::files::Files;
+use codespan_reporting::term::{self, termcolor::NoColor};
+use srcpos_get::ext::codespan::*;
+use srcpos_get::*;
+
+#[test]
+fn test_files() {
+    let mut map = SourceMap::new();
+    let file = map.add("a.x", "ab\nc变d\n");
+    assert_eq!(Files::line_range(&map, file, 0).unwrap(), 0..3);
+    assert_eq!(Files::line_range(&map, file, 1).unwrap(), 3..9);
+    assert_eq!(Files::line_range(&map, file, 2).unwrap(), 9..9);
+    assert!(Files::line_range(&map, file, 3).is_err());
+    let l = Files::location(&map, file, 7).unwrap();
+    assert_eq!((l.line_number, l.column_number), (2, 3));
+}
+
+#[test]
+fn test_emit() {
+    let mut map = SourceMap::new();
+    let file = map.add("a.x", "let a = b;\n");
+    let d = file_diagnostic(
+        Severity::Error,
+        &map,
+        &FileLoc::new(file, locof!(0, 8, 0, 9)),
+        "cannot find value `b`",
+    )
+    .unwrap();
+    let mut out = NoColor::new(vec![]);
+    term::emit_to_write_style(&mut out, &term::Config::default(), &map, &d).unwrap();
+    let out = String::from_utf8(out.into_inner()).unwrap();
+    assert!(out.contains("a.x:1:9"), "{}", out);
+}
@@ -0,0 +1,90 @@
+use srcpos_get::diagnostic::*;
+use srcpos_get::*;
+
+#[test]
+fn test_multi_line() {
+    let source = "fn main() {\n    foo(\n        1,\n    );\n}\n";
+    let d = Diagnostic::warning("unused result", &locof!(1, 4, 3, 5))
+        .with_label("this call")
+        .with_note("results should be used");
+    assert_eq!(
+        d.render(source),
+        "\
+warning: unused result
+ --> 2:5
+  |
+2 |     foo(
+  |     ^^^^
+3 |         1,
+  | ^^^^^^^^^^
+4 |     );
+  | ^^^^^ this call
+  |
+  = note: results should be used
+"
+    );
+}
+
+#[test]
+fn test_gap_and_pos() {
+    let source = "a\n\n\n\n\n\n\n\n\nbc\n";
+    let d = Diagnostic::at_pos(Severity::Error, "here", &pos(9, 1))
+        .with_secondary(&locof!(0, 0, 0, 1), "from here");
+    assert_eq!(
+        d.render_file("x", source),
+        "\
+error: here
+  --> x:10:2
+   |
+ 1 | a
+   | - from here
+...
+10 | bc
+   |  ^
+"
+    );
+}
+
+#[test]
+fn test_whole_line() {
+    let source = "ab\ncd\n";
+    let d = Diagnostic::error("bad line", &locof!(0, 0, 1, 0)).with_label("this line");
+    assert_eq!(
+        d.render(source),
+        "\
+error: bad line
+ --> 1:1
+  |
+1 | ab
+  | ^^ this line
+"
+    );
+}
+
+#[test]
+fn test_wide_chars() {
+    let source = "let 变量 = x;\n";
+    let d = Diagnostic::error("bad assignment", &locof!(0, 7, 0, 8))
+        .with_label("here")
+        .with_secondary(&locof!(0, 4, 0, 6), "name");
+    assert_eq!(
+        d.render(source),
+        "\
+error: bad assignment
+ --> 1:8
+  |
+1 | let 变量 = x;
+  |          ^ here
+  |     ---- name
+"
+    );
+}
+
+#[test]
+fn test_crlf() {
+    let d = Diagnostic::error("bad", &locof!(1, 0, 1, 2));
+    assert_eq!(
+        d.render("ab\r\ncd\r\n"),
+        "error: bad\n --> 2:1\n  |\n2 | cd\n  | ^^\n"
+    );
+}
@@ -0,0 +1,58 @@
+use srcpos_get::*;
+use std::borrow::Cow;
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::sync::Arc;
+
+fn loc_of<T: GetLoc + ?Sized>(v: &T) -> Loc {
+    v.loc()
+}
+
+fn pos_of<T: GetPos + ?Sized>(v: &T) -> Pos {
+    v.pos()
+}
+
+#[test]
+fn test_smart_pointer() {
+    let l = locof!(0, 1, 2, 3);
+    let b: Box<dyn GetLoc> = Box::new(l);
+    assert_eq!(loc_of(&b), l);
+    assert_eq!(loc_of(&Rc::new(l)), l);
+    assert_eq!(loc_of(&Arc::new(l)), l);
+    assert_eq!(loc_of(&Cow::Borrowed(&l)), l);
+    assert_eq!(loc_of(&Box::pin(l)), l);
+    assert_eq!(loc_of(&RefCell::new(l)), l);
+    assert_eq!(pos_of(&Rc::new(l)), pos(0, 1));
+}
+
+#[test]
+fn test_tuple() {
+    let t = (locof!(0, 4, 0, 5), locof!(0, 0, 0, 1));
+    assert_eq!(loc_of(&t), locof!(0, 0, 0, 5));
+    assert_eq!(pos_of(&(pos(1, 2), 'a')), pos(1, 2));
+}
+
+#[test]
+fn test_array() {
+    let a = [locof!(0, 0, 0, 1), locof!(0, 4, 0, 5)];
+    assert_eq!(a.try_loc(), Some(locof!(0, 0, 0, 5)));
+    let a: [Loc; 0] = [];
+    assert_eq!(a.try_loc(), None);
+    let a: [Option<Loc>; 2] = [None, Some(locof!(0, 2, 0, 3))];
+    assert_eq!(a.try_loc(), Some(locof!(0, 2, 0, 3)));
+}
+
+#[test]
+fn test_try_list() {
+    let v = vec![
+        None,
+        Some(locof!(0, 2, 0, 3)),
+        Some(locof!(1, 0, 1, 1)),
+        None,
+    ];
+    assert_eq!(v.try_loc(), Some(locof!(0, 2, 1, 1)));
+    let v: Vec<Option<Loc>> = vec![None, None];
+    assert_eq!(v.try_loc(), None);
+    let v = vec![vec![], vec![locof!(0, 2, 0, 3)]];
+    assert_eq!(v.try_loc(), Some(locof!(0, 2, 0, 3)));
+}
@@ -0,0 +1,30 @@
+#![cfg(feature = "lalrpop")]
+
+use srcpos_get::ext::lalrpop::PosLexer;
+use srcpos_get::*;
+
+#[test]
+fn test_pos_lexer() {
+    let source = "变 \"a\nb\"\n c";
+    let tokens = vec![
+        Ok((0, 'v', 3)),
+        Ok((4, 's', 9)),
+        Err("bad"),
+        Ok((11, 'c', 12)),
+        Ok((0, 'v', 3)),
+    ];
+    let tokens: Vec<_> = PosLexer::new(source, tokens).collect();
+    assert_eq!(tokens[0], Ok((pos(0, 0), 'v', pos(0, 1))));
+    assert_eq!(tokens[1], Ok((pos(0, 2), 's', pos(1, 2))));
+    assert_eq!(tokens[2], Err("bad"));
+    assert_eq!(tokens[3], Ok((pos(2, 1), 'c', pos(2, 2))));
+    assert_eq!(tokens[4], Ok((pos(0, 0), 'v', pos(0, 1))));
+}
+
+#[test]
+fn test_inside_char() {
+    let tokens: Vec<_> =
+        PosLexer::new("变a", vec![Ok::<_, ()>((0, 'v', 1)), Ok((3, 'a', 4))]).collect();
+    assert_eq!(tokens[0], Ok((pos(0, 0), 'v', pos(0, 0))));
+    assert_eq!(tokens[1], Ok((pos(0, 1), 'a', pos(0, 2))));
+}
@@ -0,0 +1,105 @@
+use srcpos_get::*;
+
+#[test]
+fn test_ascii() {
+    let index = LineIndex::new("ab\ncd\n");
+    assert_eq!(index.line_count(), 3);
+    assert_eq!(index.pos(0), pos(0, 0));
+    assert_eq!(index.pos(2), pos(0, 2));
+    assert_eq!(index.pos(3), pos(1, 0));
+    assert_eq!(index.pos(6), pos(2, 0));
+    assert_eq!(index.pos(100), pos(2, 0));
+    assert_eq!(index.offset(pos(1, 1)), Some(4));
+    assert_eq!(index.offset(pos(1, 2)), Some(5));
+    assert_eq!(index.offset(pos(1, 3)), None);
+    assert_eq!(index.offset(pos(3, 0)), None);
+    assert_eq!(index.line_range(1), Some(3..5));
+}
+
+#[test]
+fn test_wide() {
+    let text = "aé😀b\n变量";
+    let index = LineIndex::new(text);
+    for (i, (offset, _)) in text.char_indices().filter(|(_, c)| *c != '\n').enumerate() {
+        let p = index.pos(offset);
+        assert_eq!(index.offset(p), Some(offset), "{} {:?}", i, p);
+    }
+    assert_eq!(index.pos(3), pos(0, 2));
+    assert_eq!(index.pos(7), pos(0, 3));
+    assert_eq!(index.pos(5), pos(0, 2));
+    assert_eq!(index.pos(9), pos(1, 0));
+    assert_eq!(index.pos(12), pos(1, 1));
+    assert_eq!(index.loc(1..7), locof!(0, 1, 0, 3));
+    assert_eq!(index.range(locof!(0, 1, 1, 2)), Some(1..15));
+}
+
+#[test]
+fn test_encoding() {
+    let text = "aé😀b\n变量";
+    let index = LineIndex::new(text);
+    let p = pos(0, 3);
+    assert_eq!(index.to_encoding(p, ColumnEncoding::Utf8), Some(pos(0, 7)));
+    assert_eq!(index.to_encoding(p, ColumnEncoding::Utf16), Some(pos(0, 4)));
+    assert_eq!(index.to_encoding(p, ColumnEncoding::Utf32), Some(p));
+    assert_eq!(
+        index.from_encoding(pos(0, 7), ColumnEncoding::Utf8),
+        Some(p)
+    );
+    assert_eq!(
+        index.from_encoding(pos(0, 4), ColumnEncoding::Utf16),
+        Some(p)
+    );
+    assert_eq!(
+        index.from_encoding(pos(0, 5), ColumnEncoding::Utf16),
+        Some(pos(0, 4))
+    );
+    assert_eq!(index.from_encoding(pos(0, 6), ColumnEncoding::Utf16), None);
+    assert_eq!(
+        index.to_encoding(pos(1, 2), ColumnEncoding::Utf16),
+        Some(pos(1, 2))
+    );
+    assert_eq!(
+        index.to_encoding(pos(1, 2), ColumnEncoding::Utf8),
+        Some(pos(1, 6))
+    );
+}
+
+#[cfg(feature = "grapheme")]
+#[test]
+fn test_grapheme() {
+    let text = "👨‍👩‍👧x\nab";
+    let index = LineIndex::new(text);
+    assert_eq!(index.to_grapheme(text, pos(0, 5)), Some(pos(0, 1)));
+    assert_eq!(index.from_grapheme(text, pos(0, 1)), Some(pos(0, 5)));
+    assert_eq!(index.from_grapheme(text, pos(0, 2)), Some(pos(0, 6)));
+    assert_eq!(index.from_grapheme(text, pos(0, 3)), None);
+    assert_eq!(index.to_grapheme(text, pos(1, 1)), Some(pos(1, 1)));
+
+    let text = "e\u{301}x";
+    let index = LineIndex::new(text);
+    assert_eq!(index.to_grapheme(text, pos(0, 1)), Some(pos(0, 0)));
+    assert_eq!(index.to_grapheme(text, pos(0, 2)), Some(pos(0, 1)));
+}
+
+#[test]
+fn test_char_offset() {
+    let text = "aé😀b\n变量";
+    let index = LineIndex::new(text);
+    for (i, (offset, _)) in text.char_indices().enumerate() {
+        assert_eq!(index.char_offset(offset), i);
+    }
+    assert_eq!(index.char_offset(text.len()), text.chars().count());
+    assert_eq!(index.char_offset(4), 2);
+}
+
+#[test]
+fn test_crlf() {
+    let index = LineIndex::new("ab\r\ncd\r\n\r");
+    assert_eq!(index.line_range(0), Some(0..2));
+    assert_eq!(index.line_range(1), Some(4..6));
+    assert_eq!(index.line_range(2), Some(8..9));
+    assert_eq!(index.offset(pos(0, 2)), Some(2));
+    assert_eq!(index.offset(pos(0, 3)), None);
+    assert_eq!(index.clamp_offset(pos(0, 9)), 2);
+    assert_eq!(index.pos(4), pos(1, 0));
+}
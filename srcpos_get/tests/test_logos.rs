@@ -0,0 +1,24 @@
+#![cfg(feature = "logos")]
+
+use logos::Logos;
+use srcpos_get::ext::logos::*;
+use srcpos_get::*;
+
+#[derive(Logos, Debug, Clone, Copy, PartialEq)]
+#[logos(skip r"[ \t\n]+")]
+enum Tok {
+    #[regex(r"\p{Alphabetic}+")]
+    Ident,
+    #[token("\"\n\"")]
+    Str,
+}
+
+#[test]
+fn test_tokens() {
+    let tokens: Vec<_> = LocLexer::new(Tok::lexer("变量 \"\n\"\n a ? b")).collect();
+    assert_eq!(tokens[0], Ok(Token::new(Tok::Ident, locof!(0, 0, 0, 2))));
+    assert_eq!(tokens[1], Ok(Token::new(Tok::Str, locof!(0, 3, 1, 1))));
+    assert_eq!(tokens[2].as_ref().unwrap().pos(), pos(2, 1));
+    assert_eq!(tokens[3], Err(Token::new((), locof!(2, 3, 2, 4))));
+    assert_eq!(tokens[4].as_ref().unwrap().loc(), locof!(2, 5, 2, 6));
+}
@@ -0,0 +1,62 @@
+#![cfg(feature = "lsp")]
+
+use lsp_types::{DiagnosticSeverity, Position, PositionEncodingKind, Range, Uri};
+use srcpos_get::diagnostic::Diagnostic;
+use srcpos_get::ext::lsp::*;
+use srcpos_get::*;
+use std::str::FromStr;
+
+#[test]
+fn test_encoding() {
+    let index = LineIndex::new("a😀b\nc");
+    let at = locof!(0, 1, 0, 3);
+    let utf8 = encoding(&PositionEncodingKind::UTF8);
+    let utf32 = encoding(&PositionEncodingKind::UTF32);
+    assert_eq!(
+        encoding(&PositionEncodingKind::new("x")),
+        ColumnEncoding::Utf16
+    );
+    assert_eq!(encoding_kind(utf8), PositionEncodingKind::UTF8);
+    assert_eq!(
+        range(&index, &at, utf8),
+        Range::new(Position::new(0, 1), Position::new(0, 6))
+    );
+    assert_eq!(
+        range(&index, &at, utf32),
+        Range::new(Position::new(0, 1), Position::new(0, 3))
+    );
+    assert_eq!(to_pos(&index, Position::new(0, 3), utf8), pos(0, 1));
+    assert_eq!(to_pos(&index, Position::new(0, 9), utf8), pos(0, 3));
+    assert_eq!(to_pos(&index, Position::new(5, 0), utf8), pos(1, 1));
+    assert_eq!(position(&index, pos(0, 9), utf8), Position::new(0, 6));
+}
+
+#[test]
+fn test_diagnostic() {
+    let uri = Uri::from_str("file:///a.x").unwrap();
+    let index = LineIndex::new("let a = b;\n");
+    let d = Diagnostic::error("cannot find value `b`", &locof!(0, 8, 0, 9))
+        .with_label("not found")
+        .with_secondary(&locof!(0, 4, 0, 5), "defined here")
+        .with_note("a note");
+    let r = from_diagnostic(&uri, &index, &d, ColumnEncoding::Utf16);
+    assert_eq!(r.severity, Some(DiagnosticSeverity::ERROR));
+    assert_eq!(r.message, "cannot find value `b`\nnot found\nnote: a note");
+    assert_eq!(
+        r.range,
+        Range::new(Position::new(0, 8), Position::new(0, 9))
+    );
+    let related = r.related_information.unwrap();
+    assert_eq!(related[0].location.uri, uri);
+    assert_eq!(related[0].location.range.start, Position::new(0, 4));
+    assert_eq!(related[0].message, "defined here");
+}
+
+#[test]
+fn test_crlf() {
+    let index = LineIndex::new("ab\r\ncd");
+    let utf16 = encoding(&PositionEncodingKind::UTF16);
+    assert_eq!(to_pos(&index, Position::new(0, 10), utf16), pos(0, 2));
+    assert_eq!(position(&index, pos(0, 10), utf16), Position::new(0, 2));
+    assert_eq!(to_pos(&index, Position::new(1, 1), utf16), pos(1, 1));
+}
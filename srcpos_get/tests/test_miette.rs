@@ -0,0 +1,43 @@
+#![cfg(feature = "miette")]
+
+use miette::Diagnostic;
+use srcpos_get::ext::miette::*;
+use srcpos_get::*;
+use std::fmt;
+
+#[derive(Debug)]
+struct E(Loc);
+
+impl GetLoc for E {
+    fn loc(&self) -> Loc {
+        self.0
+    }
+}
+
+impl fmt::Display for E {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("e")
+    }
+}
+
+impl std::error::Error for E {}
+
+#[test]
+fn test_span() {
+    let index = LineIndex::new("ab\nc变d\n");
+    let span = source_span(&index, &locof!(1, 1, 1, 3));
+    assert_eq!((span.offset(), span.len()), (4, 4));
+}
+
+#[test]
+fn test_labels() {
+    let mut map = SourceMap::new();
+    let file = map.add("a.x", "ab\ncd\n");
+    let e = LocError::from_file(E(locof!(1, 0, 1, 2)), map.get(file).unwrap()).with_label("here");
+    let labels: Vec<_> = e.labels().unwrap().collect();
+    assert_eq!(labels.len(), 1);
+    assert_eq!(labels[0].label(), Some("here"));
+    assert_eq!((labels[0].offset(), labels[0].len()), (3, 2));
+    assert!(e.source_code().is_some());
+    assert_eq!(e.to_string(), "e");
+}
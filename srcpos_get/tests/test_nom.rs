@@ -0,0 +1,25 @@
+#![cfg(feature = "nom")]
+
+use nom::bytes::complete::tag;
+use nom::character::complete::{alpha1, multispace0};
+use nom::sequence::preceded;
+use nom::{IResult, Parser};
+use nom_locate::LocatedSpan;
+use srcpos_get::ext::nom::located;
+use srcpos_get::*;
+
+type Span<'a> = LocatedSpan<&'a str>;
+
+fn block(input: Span) -> IResult<Span, (Loc, Span)> {
+    located(preceded(tag("变{"), preceded(multispace0, alpha1))).parse(input)
+}
+
+#[test]
+fn test_located() {
+    let (rest, (loc, name)) = preceded(multispace0, block)
+        .parse(Span::new(" \n变{ ab}"))
+        .unwrap();
+    assert_eq!(loc, locof!(1, 0, 1, 5));
+    assert_eq!(name.loc(), locof!(1, 3, 1, 5));
+    assert_eq!(rest.pos(), pos(1, 5));
+}
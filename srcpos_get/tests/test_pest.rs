@@ -0,0 +1,30 @@
+#![cfg(feature = "pest")]
+
+use pest::Parser;
+use pest_derive::Parser;
+use srcpos_get::*;
+
+#[derive(Parser)]
+#[grammar_inline = r#"
+word = @{ (!WHITESPACE ~ ANY)+ }
+main = { SOI ~ word* ~ EOI }
+WHITESPACE = _{ " " | "\t" | NEWLINE }
+"#]
+struct Grammar;
+
+#[test]
+fn test_pair() {
+    let main = Grammar::parse(Rule::main, "a 变量\r\n  bc")
+        .unwrap()
+        .next()
+        .unwrap();
+    assert_eq!(main.loc(), locof!(0, 0, 1, 4));
+    let words: Vec<_> = main
+        .into_inner()
+        .filter(|p| p.as_rule() == Rule::word)
+        .collect();
+    assert_eq!(words[1].loc(), locof!(0, 2, 0, 4));
+    assert_eq!(words[2].pos(), pos(1, 2));
+    assert_eq!(words[2].as_span().loc(), locof!(1, 2, 1, 4));
+    assert_eq!(GetPos::pos(&words[2].as_span().end_pos()), pos(1, 4));
+}
@@ -0,0 +1,17 @@
+#![cfg(feature = "proc-macro2")]
+
+use proc_macro2::{TokenStream, TokenTree};
+use srcpos_get::*;
+
+#[test]
+fn test_tokens() {
+    let tokens: Vec<TokenTree> = "a (b\n  c) 'd'"
+        .parse::<TokenStream>()
+        .unwrap()
+        .into_iter()
+        .collect();
+    assert_eq!(tokens[0].loc(), locof!(0, 0, 0, 1));
+    assert_eq!(tokens[1].loc(), locof!(0, 2, 1, 4));
+    assert_eq!(tokens[2].pos(), pos(1, 5));
+    assert_eq!(tokens[2].loc(), locof!(1, 5, 1, 8));
+}
@@ -0,0 +1,82 @@
+#![cfg(feature = "rowan")]
+
+use rowan::{GreenNodeBuilder, Language, NodeOrToken, SyntaxKind, TextRange};
+use srcpos_get::*;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+enum Lang {}
+
+impl Language for Lang {
+    type Kind = u16;
+    fn kind_from_raw(raw: SyntaxKind) -> u16 {
+        raw.0
+    }
+    fn kind_to_raw(kind: u16) -> SyntaxKind {
+        SyntaxKind(kind)
+    }
+}
+
+type SyntaxNode = rowan::SyntaxNode<Lang>;
+
+fn tree() -> SyntaxNode {
+    let mut builder = GreenNodeBuilder::new();
+    builder.start_node(SyntaxKind(0));
+    builder.token(SyntaxKind(1), "变 ");
+    builder.start_node(SyntaxKind(2));
+    builder.token(SyntaxKind(1), "a\n");
+    builder.token(SyntaxKind(1), "bc");
+    builder.finish_node();
+    builder.finish_node();
+    SyntaxNode::new_root(builder.finish())
+}
+
+#[test]
+fn test_indexed() {
+    let root = tree();
+    let index = LineIndex::new(&root.text().to_string());
+    let node = root.last_child().unwrap();
+    assert_eq!(Indexed::new(&index, &root).loc(), locof!(0, 0, 1, 2));
+    assert_eq!(Indexed::new(&index, &node).loc(), locof!(0, 2, 1, 2));
+    let token = node.last_token().unwrap();
+    assert_eq!(Indexed::new(&index, &token).loc(), locof!(1, 0, 1, 2));
+    let v = NodeOrToken::<SyntaxNode, _>::Token(token);
+    assert_eq!(Indexed::new(&index, v).pos(), pos(1, 0));
+    let range = TextRange::new(4.into(), 7.into());
+    assert_eq!(Indexed::new(&index, range).loc(), locof!(0, 2, 1, 1));
+}
+
+#[derive(GetByteRange)]
+struct Item {
+    #[loc]
+    syntax: SyntaxNode,
+}
+
+#[derive(GetByteRange)]
+enum Element {
+    Item(Item),
+    Token(rowan::SyntaxToken<Lang>),
+}
+
+#[derive(GetLoc)]
+struct Located<'a> {
+    #[loc]
+    item: Indexed<'a, &'a Item>,
+}
+
+#[test]
+fn test_derive() {
+    let root = tree();
+    let index = LineIndex::new(&root.text().to_string());
+    let item = Item {
+        syntax: root.last_child().unwrap(),
+    };
+    assert_eq!(Indexed::new(&index, &item).loc(), locof!(0, 2, 1, 2));
+    let root = Element::Item(Item { syntax: root });
+    assert_eq!(Indexed::new(&index, &root).loc(), locof!(0, 0, 1, 2));
+    let token = Element::Token(item.syntax.last_token().unwrap());
+    assert_eq!(Indexed::new(&index, &token).loc(), locof!(1, 0, 1, 2));
+    let v = Located {
+        item: Indexed::new(&index, &item),
+    };
+    assert_eq!(v.loc(), locof!(0, 2, 1, 2));
+}
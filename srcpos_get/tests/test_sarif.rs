@@ -0,0 +1,116 @@
+#![cfg(feature = "sarif")]
+
+use serde_json::json;
+use srcpos_get::diagnostic::{Diagnostic, Severity};
+use srcpos_get::ext::sarif::*;
+use srcpos_get::*;
+
+#[test]
+fn test_region() {
+    assert_eq!(
+        region(&locof!(0, 0, 1, 3)),
+        json!({ "startLine": 1, "startColumn": 1, "endLine": 2, "endColumn": 4 })
+    );
+    assert_eq!(uri("src\\a b/变.x"), "src/a%20b/%E5%8F%98.x");
+    assert_eq!(uri("C:\\src\\a.rs"), "file:///C:/src/a.rs");
+    assert_eq!(uri("/src/a.rs"), "file:///src/a.rs");
+    assert_eq!(uri("\\\\server\\share\\a.rs"), "file://server/share/a.rs");
+    assert_eq!(uri("c:a.rs"), "c%3Aa.rs");
+    assert_eq!(level(Severity::Help), "note");
+}
+
+#[test]
+fn test_log() {
+    let mut map = SourceMap::new();
+    let file = map.add("src/main.x", "let a = b;\n");
+    let mut sarif = Sarif::new("checker").with_version("1.0.0");
+    sarif
+        .add(
+            "unused",
+            Severity::Warning,
+            "src/a.x",
+            &locof!(0, 4, 0, 5),
+            "unused",
+        )
+        .add(
+            "unused",
+            Severity::Warning,
+            "src/b.x",
+            &Loc::new_same_pos(pos(1, 2)),
+            "unused",
+        );
+    assert!(sarif
+        .add_file(
+            &map,
+            "undefined",
+            Severity::Error,
+            &FileLoc::new(file, locof!(0, 8, 0, 9)),
+            "undefined"
+        )
+        .is_some());
+    assert!(sarif
+        .add_file(
+            &map,
+            "undefined",
+            Severity::Error,
+            &FileLoc::new(FileId(9), locof!(0, 8, 0, 9)),
+            "undefined"
+        )
+        .is_none());
+    assert_eq!(sarif.len(), 3);
+
+    let log = sarif.to_value();
+    assert_eq!(log["version"], "2.1.0");
+    let run = &log["runs"][0];
+    assert_eq!(run["columnKind"], "unicodeCodePoints");
+    assert_eq!(
+        run["tool"]["driver"],
+        json!({ "name": "checker", "version": "1.0.0", "rules": [{ "id": "unused" }, { "id": "undefined" }] })
+    );
+    assert_eq!(
+        run["results"][1],
+        json!({
+            "ruleId": "unused",
+            "ruleIndex": 0,
+            "level": "warning",
+            "message": { "text": "unused" },
+            "locations": [{
+                "physicalLocation": {
+                    "artifactLocation": { "uri": "src/b.x" },
+                    "region": { "startLine": 2, "startColumn": 3, "endLine": 2, "endColumn": 3 },
+                }
+            }],
+        })
+    );
+    assert_eq!(run["results"][2]["ruleIndex"], 1);
+    assert_eq!(
+        run["results"][2]["locations"][0]["physicalLocation"]["artifactLocation"]["uri"],
+        "src/main.x"
+    );
+    assert_eq!(
+        serde_json::from_str::<serde_json::Value>(&sarif.to_json()).unwrap(),
+        log
+    );
+}
+
+#[test]
+fn test_diagnostic() {
+    let d = Diagnostic::error("cannot find value `b`", &locof!(0, 8, 0, 9))
+        .with_label("not found")
+        .with_secondary(&locof!(0, 4, 0, 5), "defined here")
+        .with_note("a note");
+    let mut sarif = Sarif::new("checker");
+    sarif.add_diagnostic("E0425", "main.x", &d);
+    let r = &sarif.to_value()["runs"][0]["results"][0];
+    assert_eq!(r["level"], "error");
+    assert_eq!(
+        r["message"]["text"],
+        "cannot find value `b`\nnot found\nnote: a note"
+    );
+    assert_eq!(
+        r["relatedLocations"][0]["physicalLocation"]["region"],
+        json!({ "startLine": 1, "startColumn": 5, "endLine": 1, "endColumn": 6 })
+    );
+    assert_eq!(r["relatedLocations"][0]["message"]["text"], "defined here");
+    assert_eq!(r["relatedLocations"][0]["id"], 0);
+}
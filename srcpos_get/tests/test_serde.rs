@@ -0,0 +1,53 @@
+#![cfg(feature = "serde")]
+
+use serde::{Deserialize, Serialize};
+use srcpos_get::ext::serde::Repr;
+use srcpos_get::*;
+
+#[test]
+fn test_struct() {
+    let loc = FileLoc::new(FileId(2), locof!(3, 5, 4, 1));
+    let json = serde_json::to_string(&loc).unwrap();
+    assert_eq!(
+        json,
+        r#"{"file":2,"loc":{"from":{"line":3,"column":5},"to":{"line":4,"column":1}}}"#
+    );
+    assert_eq!(serde_json::from_str::<FileLoc>(&json).unwrap(), loc);
+}
+
+#[test]
+fn test_repr() {
+    let loc = FileLoc::new(FileId(2), locof!(3, 5, 4, 1));
+    assert_eq!(loc.to_compact(), vec![2, 3, 5, 4, 1]);
+    assert_eq!(FileLoc::from_compact(&[2, 3, 5, 4, 1]), Some(loc));
+    assert_eq!(FileLoc::from_compact(&[2, 3, 5, 4]), None);
+    assert_eq!(loc.to_repr_string(), "#2 3:5-4:1");
+    assert_eq!(FileLoc::from_repr_str("#2 3:5-4:1"), Some(loc));
+    assert_eq!(FileLoc::from_repr_str("2 3:5-4:1"), None);
+    assert_eq!(Pos::from_repr_str("3:x"), None);
+    assert_eq!(Loc::from_repr_str("3:5"), None);
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+struct A {
+    #[serde(with = "srcpos_get::ext::serde::compact")]
+    a: Pos,
+    #[serde(with = "srcpos_get::ext::serde::string")]
+    b: Loc,
+}
+
+#[test]
+fn test_with() {
+    let a = A {
+        a: pos(1, 2),
+        b: locof!(1, 2, 3, 4),
+    };
+    let json = serde_json::to_string(&a).unwrap();
+    assert_eq!(json, r#"{"a":[1,2],"b":"1:2-3:4"}"#);
+    assert_eq!(serde_json::from_str::<A>(&json).unwrap(), a);
+
+    let e = serde_json::from_str::<A>(r#"{"a":[1],"b":"1:2-3:4"}"#).unwrap_err();
+    assert!(e.to_string().contains("[line, column]"), "{}", e);
+    let e = serde_json::from_str::<A>(r#"{"a":[1,2],"b":"1:2"}"#).unwrap_err();
+    assert!(e.to_string().contains("line:column-line:column"), "{}", e);
+}
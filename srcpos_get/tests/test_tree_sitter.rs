@@ -0,0 +1,40 @@
+#![cfg(feature = "tree-sitter")]
+
+use srcpos_get::ext::tree_sitter::*;
+use srcpos_get::*;
+use tree_sitter::{Point, Range};
+
+#[test]
+fn test_range() {
+    let index = LineIndex::new("a\n变量 b");
+    let range = Range {
+        start_byte: 9,
+        end_byte: 10,
+        start_point: Point::new(1, 7),
+        end_point: Point::new(1, 8),
+    };
+    assert_eq!(Indexed::new(&index, range).loc(), locof!(1, 3, 1, 4));
+    assert_eq!(Indexed::new(&index, &range).pos(), pos(1, 3));
+    assert_eq!(char_pos(&index, range.end_point), pos(1, 4));
+    assert_eq!(char_pos(&index, Point::new(5, 0)), pos(1, 4));
+}
+
+#[test]
+fn test_input_edit() {
+    let index = LineIndex::new("a\n变量 b");
+    let edit = input_edit(&index, locof!(0, 1, 1, 1), "xy\n变");
+    assert_eq!(
+        (edit.start_byte, edit.old_end_byte, edit.new_end_byte),
+        (1, 5, 7)
+    );
+    assert_eq!(edit.start_position, Point::new(0, 1));
+    assert_eq!(edit.old_end_position, Point::new(1, 3));
+    assert_eq!(edit.new_end_position, Point::new(1, 3));
+    let edit = input_edit(&index, locof!(1, 3, 9, 0), "c");
+    assert_eq!(
+        (edit.start_byte, edit.old_end_byte, edit.new_end_byte),
+        (9, 10, 10)
+    );
+    assert_eq!(edit.old_end_position, Point::new(1, 8));
+    assert_eq!(edit.new_end_position, Point::new(1, 8));
+}